@@ -0,0 +1,35 @@
+use std::io::{self, Cursor, Read};
+
+use flate2::read::MultiGzDecoder;
+use zip::ZipArchive;
+
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Decompresses a font file that is gzipped or zipped, recognising both by their magic
+/// bytes rather than the file name. Anything else is returned unchanged.
+///
+/// Like figlet, a zip archive is expected to hold the font as its first file.
+pub fn unpack(bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        let mut content = Vec::new();
+        MultiGzDecoder::new(&bytes[..]).read_to_end(&mut content)?;
+        Ok(content)
+    } else if bytes.starts_with(ZIP_MAGIC) {
+        let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            if file.is_file() {
+                let mut content = Vec::new();
+                file.read_to_end(&mut content)?;
+                return Ok(content);
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "zip archive does not contain a font",
+        ))
+    } else {
+        Ok(bytes)
+    }
+}
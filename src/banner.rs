@@ -0,0 +1,192 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Range;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::color::{ColorDepth, Rgb};
+use crate::comment::{self, CommentOptions};
+use crate::html::{self, HtmlOptions};
+use crate::json::{self, JsonOptions};
+use crate::png::{self, PngOptions};
+use crate::svg::{self, SvgOptions};
+
+/// Form a [`Banner`] is written out in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Plain text, one line per row followed by an empty line, like figlet.
+    #[default]
+    Text,
+    /// Text with the banner's colours as ANSI escape sequences of the given depth.
+    Ansi(ColorDepth),
+    /// A `<pre>` block or standalone HTML document, with the colours as inline styles.
+    Html(HtmlOptions),
+    /// An SVG image drawing every cell as text or as a filled rectangle.
+    Svg(SvgOptions),
+    /// A PNG image drawing every cell with the built-in bitmap font.
+    Png(PngOptions),
+    /// A JSON document with the rows, where every character was drawn and how.
+    Json(JsonOptions),
+    /// Rows commented out in the syntax of a programming or markup language.
+    Comment(CommentOptions),
+}
+
+/// Where the FIGcharacter of one character of the message was drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Position of the character in the banner's [`text`](Banner::text).
+    pub index: usize,
+    /// Character code the FIGcharacter was drawn for, after translation by control files
+    /// and replacement of missing characters.
+    pub code: i64,
+    pub rows: Range<usize>,
+    /// Terminal columns, which overlap those of neighbouring FIGcharacters when they were
+    /// smushed together.
+    pub columns: Range<usize>,
+}
+
+/// A rendered message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Banner {
+    /// Output rows with hardblanks already turned into spaces.
+    pub rows: Vec<String>,
+    /// Message the banner was rendered from, decoded and normalized, which the indices of
+    /// `placements` point into.
+    pub text: String,
+    /// Where every character of the message was drawn, in the order they were added.
+    pub placements: Vec<Placement>,
+    /// Colour of every terminal column of every row, empty when the banner is not coloured.
+    pub colors: Vec<Vec<Option<Rgb>>>,
+    pub format: OutputFormat,
+}
+
+impl Banner {
+    /// Width of the widest row in terminal columns.
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(|row| width(row)).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Colour of the sub-character starting at `column` of `row`, if any.
+    pub fn color(&self, row: usize, column: usize) -> Option<Rgb> {
+        self.colors.get(row)?.get(column).copied().flatten()
+    }
+
+    /// Sub-characters of `row` with the terminal column each starts at.
+    pub fn cells(&self, row: usize) -> impl Iterator<Item = (usize, char)> + '_ {
+        self.rows[row].chars().scan(0, |column, c| {
+            let start = *column;
+            *column += c.width().unwrap_or(0);
+            Some((start, c))
+        })
+    }
+
+    /// Writes the banner to `out` in its output format.
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        match &self.format {
+            OutputFormat::Text => writeln!(out, "{}", self),
+            OutputFormat::Ansi(depth) => {
+                for row in 0..self.height() {
+                    self.write_ansi_row(out, row, *depth)?;
+                }
+                writeln!(out)
+            }
+            OutputFormat::Html(options) => html::write(self, options, out),
+            OutputFormat::Svg(options) => svg::write(self, options, out),
+            OutputFormat::Png(options) => png::write(self, options, out),
+            OutputFormat::Json(options) => json::write(self, options, out),
+            OutputFormat::Comment(options) => comment::write(self, options, out),
+        }
+    }
+
+    /// Writes `row` with an escape sequence wherever the colour of a visible sub-character
+    /// changes, resetting the colour at the end of the row.
+    fn write_ansi_row(
+        &self,
+        out: &mut impl Write,
+        row: usize,
+        depth: ColorDepth,
+    ) -> io::Result<()> {
+        let mut current = None;
+        for (column, c) in self.cells(row) {
+            if c != ' ' {
+                let color = self.color(row, column);
+                if color != current {
+                    match color {
+                        Some(color) => write!(out, "{}", color.escape(depth))?,
+                        None => write!(out, "{}", ColorDepth::RESET)?,
+                    }
+                    current = color;
+                }
+            }
+            write!(out, "{}", c)?;
+        }
+        if current.is_some() {
+            write!(out, "{}", ColorDepth::RESET)?;
+        }
+        writeln!(out)
+    }
+}
+
+/// Width of `text` in terminal columns, measured one sub-character at a time like the
+/// [`cells`](Banner::cells) of a row, rather than as a string whose character sequences
+/// may be narrower or wider than their parts.
+pub(crate) fn width(text: &str) -> usize {
+    text.chars().map(|c| c.width().unwrap_or(0)).sum()
+}
+
+/// Formats the banner as plain text, whatever its output format.
+impl fmt::Display for Banner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banner(rows: &[&str]) -> Banner {
+        Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: Vec::new(),
+            colors: Vec::new(),
+            format: OutputFormat::Text,
+        }
+    }
+
+    #[test]
+    fn measures_and_writes_text() {
+        let banner = banner(&[" _ ", "|_|", ""]);
+        assert_eq!(banner.width(), 3);
+        assert_eq!(banner.height(), 3);
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        assert_eq!(out, b" _ \n|_|\n\n\n");
+    }
+
+    #[test]
+    fn writes_colours_as_escape_sequences() {
+        let red = Some(Rgb::new(255, 0, 0));
+        let mut banner = banner(&["a b全c", "xy"]);
+        banner.colors = vec![vec![red, red, red, None, None, red], vec![]];
+        banner.format = OutputFormat::Ansi(ColorDepth::TrueColor);
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[38;2;255;0;0ma b\x1b[0m全\x1b[38;2;255;0;0mc\x1b[0m\nxy\n\n"
+        );
+        assert_eq!(
+            banner.cells(0).collect::<Vec<_>>(),
+            [(0, 'a'), (1, ' '), (2, 'b'), (3, '全'), (5, 'c')]
+        );
+    }
+}
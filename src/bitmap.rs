@@ -0,0 +1,207 @@
+//! Built-in bitmap font of the PNG output, so rasterizing a banner needs no font files.
+//!
+//! Printable ASCII characters are drawn from a 5x7 pixel font in 6x8 cells, which leaves
+//! room for descenders and a column between characters. The characters FIGlet fonts draw
+//! their lines with reach the edges of their cells instead, so `|`, `/`, `\`, `_`, `-` and
+//! `=` join those of neighbouring cells into unbroken strokes. Block elements and light
+//! box-drawing characters are drawn by shape, anything else as an empty box.
+
+/// Size of a cell one column wide, in pixels.
+pub(crate) const CELL_WIDTH: usize = 6;
+pub(crate) const CELL_HEIGHT: usize = 8;
+
+/// Row that horizontal lines of box-drawing characters are drawn on, the same as `-`.
+const MIDDLE_ROW: usize = 3;
+/// Column that vertical lines of box-drawing characters are drawn on, the same as `|`.
+const MIDDLE_COLUMN: usize = 2;
+
+/// Rows of the characters from `' '` to `'~'`, the leftmost pixel in the highest bit.
+#[rustfmt::skip]
+const GLYPHS: [[u8; CELL_HEIGHT]; 95] = [
+    [0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000], // ' '
+    [0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b000000, 0b001000, 0b000000], // '!'
+    [0b010100, 0b010100, 0b010100, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000], // '"'
+    [0b010100, 0b010100, 0b111110, 0b010100, 0b111110, 0b010100, 0b010100, 0b000000], // '#'
+    [0b001000, 0b011110, 0b101000, 0b011100, 0b001010, 0b111100, 0b001000, 0b000000], // '$'
+    [0b110000, 0b110010, 0b000100, 0b001000, 0b010000, 0b100110, 0b000110, 0b000000], // '%'
+    [0b011000, 0b100100, 0b101000, 0b010000, 0b101010, 0b100100, 0b011010, 0b000000], // '&'
+    [0b001000, 0b001000, 0b001000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000], // '\''
+    [0b000100, 0b001000, 0b010000, 0b010000, 0b010000, 0b001000, 0b000100, 0b000000], // '('
+    [0b010000, 0b001000, 0b000100, 0b000100, 0b000100, 0b001000, 0b010000, 0b000000], // ')'
+    [0b000000, 0b001000, 0b101010, 0b011100, 0b101010, 0b001000, 0b000000, 0b000000], // '*'
+    [0b000000, 0b001000, 0b001000, 0b111110, 0b001000, 0b001000, 0b000000, 0b000000], // '+'
+    [0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b011000, 0b001000, 0b010000], // ','
+    [0b000000, 0b000000, 0b000000, 0b111111, 0b000000, 0b000000, 0b000000, 0b000000], // '-'
+    [0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b011000, 0b011000, 0b000000], // '.'
+    [0b000001, 0b000010, 0b000010, 0b000100, 0b001000, 0b010000, 0b010000, 0b100000], // '/'
+    [0b011100, 0b100010, 0b100110, 0b101010, 0b110010, 0b100010, 0b011100, 0b000000], // '0'
+    [0b001000, 0b011000, 0b001000, 0b001000, 0b001000, 0b001000, 0b011100, 0b000000], // '1'
+    [0b011100, 0b100010, 0b000010, 0b000100, 0b001000, 0b010000, 0b111110, 0b000000], // '2'
+    [0b111110, 0b000100, 0b001000, 0b000100, 0b000010, 0b100010, 0b011100, 0b000000], // '3'
+    [0b000100, 0b001100, 0b010100, 0b100100, 0b111110, 0b000100, 0b000100, 0b000000], // '4'
+    [0b111110, 0b100000, 0b111100, 0b000010, 0b000010, 0b100010, 0b011100, 0b000000], // '5'
+    [0b001100, 0b010000, 0b100000, 0b111100, 0b100010, 0b100010, 0b011100, 0b000000], // '6'
+    [0b111110, 0b000010, 0b000100, 0b001000, 0b010000, 0b010000, 0b010000, 0b000000], // '7'
+    [0b011100, 0b100010, 0b100010, 0b011100, 0b100010, 0b100010, 0b011100, 0b000000], // '8'
+    [0b011100, 0b100010, 0b100010, 0b011110, 0b000010, 0b000100, 0b011000, 0b000000], // '9'
+    [0b000000, 0b011000, 0b011000, 0b000000, 0b011000, 0b011000, 0b000000, 0b000000], // ':'
+    [0b000000, 0b011000, 0b011000, 0b000000, 0b011000, 0b001000, 0b010000, 0b000000], // ';'
+    [0b000100, 0b001000, 0b010000, 0b100000, 0b010000, 0b001000, 0b000100, 0b000000], // '<'
+    [0b000000, 0b000000, 0b111111, 0b000000, 0b111111, 0b000000, 0b000000, 0b000000], // '='
+    [0b010000, 0b001000, 0b000100, 0b000010, 0b000100, 0b001000, 0b010000, 0b000000], // '>'
+    [0b011100, 0b100010, 0b000010, 0b000100, 0b001000, 0b000000, 0b001000, 0b000000], // '?'
+    [0b011100, 0b100010, 0b000010, 0b011010, 0b101010, 0b101010, 0b011100, 0b000000], // '@'
+    [0b011100, 0b100010, 0b100010, 0b111110, 0b100010, 0b100010, 0b100010, 0b000000], // 'A'
+    [0b111100, 0b100010, 0b100010, 0b111100, 0b100010, 0b100010, 0b111100, 0b000000], // 'B'
+    [0b011100, 0b100010, 0b100000, 0b100000, 0b100000, 0b100010, 0b011100, 0b000000], // 'C'
+    [0b111000, 0b100100, 0b100010, 0b100010, 0b100010, 0b100100, 0b111000, 0b000000], // 'D'
+    [0b111110, 0b100000, 0b100000, 0b111100, 0b100000, 0b100000, 0b111110, 0b000000], // 'E'
+    [0b111110, 0b100000, 0b100000, 0b111100, 0b100000, 0b100000, 0b100000, 0b000000], // 'F'
+    [0b011100, 0b100010, 0b100000, 0b101110, 0b100010, 0b100010, 0b011110, 0b000000], // 'G'
+    [0b100010, 0b100010, 0b100010, 0b111110, 0b100010, 0b100010, 0b100010, 0b000000], // 'H'
+    [0b011100, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b011100, 0b000000], // 'I'
+    [0b001110, 0b000100, 0b000100, 0b000100, 0b000100, 0b100100, 0b011000, 0b000000], // 'J'
+    [0b100010, 0b100100, 0b101000, 0b110000, 0b101000, 0b100100, 0b100010, 0b000000], // 'K'
+    [0b100000, 0b100000, 0b100000, 0b100000, 0b100000, 0b100000, 0b111110, 0b000000], // 'L'
+    [0b100010, 0b110110, 0b101010, 0b101010, 0b100010, 0b100010, 0b100010, 0b000000], // 'M'
+    [0b100010, 0b100010, 0b110010, 0b101010, 0b100110, 0b100010, 0b100010, 0b000000], // 'N'
+    [0b011100, 0b100010, 0b100010, 0b100010, 0b100010, 0b100010, 0b011100, 0b000000], // 'O'
+    [0b111100, 0b100010, 0b100010, 0b111100, 0b100000, 0b100000, 0b100000, 0b000000], // 'P'
+    [0b011100, 0b100010, 0b100010, 0b100010, 0b101010, 0b100100, 0b011010, 0b000000], // 'Q'
+    [0b111100, 0b100010, 0b100010, 0b111100, 0b101000, 0b100100, 0b100010, 0b000000], // 'R'
+    [0b011110, 0b100000, 0b100000, 0b011100, 0b000010, 0b000010, 0b111100, 0b000000], // 'S'
+    [0b111110, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b000000], // 'T'
+    [0b100010, 0b100010, 0b100010, 0b100010, 0b100010, 0b100010, 0b011100, 0b000000], // 'U'
+    [0b100010, 0b100010, 0b100010, 0b100010, 0b100010, 0b010100, 0b001000, 0b000000], // 'V'
+    [0b100010, 0b100010, 0b100010, 0b101010, 0b101010, 0b101010, 0b010100, 0b000000], // 'W'
+    [0b100010, 0b100010, 0b010100, 0b001000, 0b010100, 0b100010, 0b100010, 0b000000], // 'X'
+    [0b100010, 0b100010, 0b010100, 0b001000, 0b001000, 0b001000, 0b001000, 0b000000], // 'Y'
+    [0b111110, 0b000010, 0b000100, 0b001000, 0b010000, 0b100000, 0b111110, 0b000000], // 'Z'
+    [0b011100, 0b010000, 0b010000, 0b010000, 0b010000, 0b010000, 0b011100, 0b000000], // '['
+    [0b100000, 0b010000, 0b010000, 0b001000, 0b000100, 0b000010, 0b000010, 0b000001], // '\\'
+    [0b011100, 0b000100, 0b000100, 0b000100, 0b000100, 0b000100, 0b011100, 0b000000], // ']'
+    [0b001000, 0b010100, 0b100010, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000], // '^'
+    [0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000, 0b111111], // '_'
+    [0b010000, 0b001000, 0b000100, 0b000000, 0b000000, 0b000000, 0b000000, 0b000000], // '`'
+    [0b000000, 0b000000, 0b011100, 0b000010, 0b011110, 0b100010, 0b011110, 0b000000], // 'a'
+    [0b100000, 0b100000, 0b101100, 0b110010, 0b100010, 0b100010, 0b111100, 0b000000], // 'b'
+    [0b000000, 0b000000, 0b011100, 0b100000, 0b100000, 0b100010, 0b011100, 0b000000], // 'c'
+    [0b000010, 0b000010, 0b011010, 0b100110, 0b100010, 0b100010, 0b011110, 0b000000], // 'd'
+    [0b000000, 0b000000, 0b011100, 0b100010, 0b111110, 0b100000, 0b011100, 0b000000], // 'e'
+    [0b001100, 0b010010, 0b010000, 0b111000, 0b010000, 0b010000, 0b010000, 0b000000], // 'f'
+    [0b000000, 0b000000, 0b011110, 0b100010, 0b100010, 0b011110, 0b000010, 0b011100], // 'g'
+    [0b100000, 0b100000, 0b101100, 0b110010, 0b100010, 0b100010, 0b100010, 0b000000], // 'h'
+    [0b001000, 0b000000, 0b011000, 0b001000, 0b001000, 0b001000, 0b011100, 0b000000], // 'i'
+    [0b000100, 0b000000, 0b001100, 0b000100, 0b000100, 0b000100, 0b100100, 0b011000], // 'j'
+    [0b100000, 0b100000, 0b100100, 0b101000, 0b110000, 0b101000, 0b100100, 0b000000], // 'k'
+    [0b011000, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b011100, 0b000000], // 'l'
+    [0b000000, 0b000000, 0b110100, 0b101010, 0b101010, 0b100010, 0b100010, 0b000000], // 'm'
+    [0b000000, 0b000000, 0b101100, 0b110010, 0b100010, 0b100010, 0b100010, 0b000000], // 'n'
+    [0b000000, 0b000000, 0b011100, 0b100010, 0b100010, 0b100010, 0b011100, 0b000000], // 'o'
+    [0b000000, 0b000000, 0b111100, 0b100010, 0b100010, 0b111100, 0b100000, 0b100000], // 'p'
+    [0b000000, 0b000000, 0b011110, 0b100010, 0b100010, 0b011110, 0b000010, 0b000010], // 'q'
+    [0b000000, 0b000000, 0b101100, 0b110010, 0b100000, 0b100000, 0b100000, 0b000000], // 'r'
+    [0b000000, 0b000000, 0b011110, 0b100000, 0b011100, 0b000010, 0b111100, 0b000000], // 's'
+    [0b010000, 0b010000, 0b111000, 0b010000, 0b010000, 0b010010, 0b001100, 0b000000], // 't'
+    [0b000000, 0b000000, 0b100010, 0b100010, 0b100010, 0b100110, 0b011010, 0b000000], // 'u'
+    [0b000000, 0b000000, 0b100010, 0b100010, 0b100010, 0b010100, 0b001000, 0b000000], // 'v'
+    [0b000000, 0b000000, 0b100010, 0b100010, 0b101010, 0b101010, 0b010100, 0b000000], // 'w'
+    [0b000000, 0b000000, 0b100010, 0b010100, 0b001000, 0b010100, 0b100010, 0b000000], // 'x'
+    [0b000000, 0b000000, 0b100010, 0b100010, 0b100010, 0b011110, 0b000010, 0b011100], // 'y'
+    [0b000000, 0b000000, 0b111110, 0b000100, 0b001000, 0b010000, 0b111110, 0b000000], // 'z'
+    [0b000100, 0b001000, 0b001000, 0b010000, 0b001000, 0b001000, 0b000100, 0b000000], // '{'
+    [0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000, 0b001000], // '|'
+    [0b010000, 0b001000, 0b001000, 0b000100, 0b001000, 0b001000, 0b010000, 0b000000], // '}'
+    [0b000000, 0b000000, 0b010000, 0b101010, 0b000100, 0b000000, 0b000000, 0b000000], // '~'
+];
+
+/// Whether the pixel at `x`, `y` of a `c` drawn `width` cells wide is set.
+pub(crate) fn pixel(c: char, width: usize, x: usize, y: usize) -> bool {
+    if let Some(rows) = (c as usize)
+        .checked_sub(0x20)
+        .and_then(|index| GLYPHS.get(index))
+    {
+        return rows[y] & (1 << (CELL_WIDTH - 1 - x)) != 0;
+    }
+    if let Some([left, right, up, down]) = arms(c) {
+        return (y == MIDDLE_ROW
+            && (x == MIDDLE_COLUMN
+                || (left && x < MIDDLE_COLUMN)
+                || (right && x > MIDDLE_COLUMN)))
+            || (x == MIDDLE_COLUMN && ((up && y < MIDDLE_ROW) || (down && y > MIDDLE_ROW)));
+    }
+    match c {
+        '█' => true,
+        '▀' => y < CELL_HEIGHT / 2,
+        '▄' => y >= CELL_HEIGHT / 2,
+        '▌' => x < CELL_WIDTH / 2,
+        '▐' => x >= CELL_WIDTH / 2,
+        '░' => x.is_multiple_of(2) && y.is_multiple_of(2),
+        '▒' => (x + y).is_multiple_of(2),
+        '▓' => x.is_multiple_of(2) || y.is_multiple_of(2),
+        // An empty box where a letter would be.
+        _ => {
+            let (right, bottom) = (width * CELL_WIDTH - 2, CELL_HEIGHT - 2);
+            x <= right && y <= bottom && (x == 0 || y == 0 || x == right || y == bottom)
+        }
+    }
+}
+
+/// Which lines of a light box-drawing character leave its centre to the left, right, top
+/// and bottom.
+fn arms(c: char) -> Option<[bool; 4]> {
+    Some(match c {
+        '─' => [true, true, false, false],
+        '│' => [false, false, true, true],
+        '┌' => [false, true, false, true],
+        '┐' => [true, false, false, true],
+        '└' => [false, true, true, false],
+        '┘' => [true, false, true, false],
+        '├' => [false, true, true, true],
+        '┤' => [true, false, true, true],
+        '┬' => [true, true, false, true],
+        '┴' => [true, true, true, false],
+        '┼' => [true, true, true, true],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(c: char) -> Vec<String> {
+        (0..CELL_HEIGHT)
+            .map(|y| {
+                (0..CELL_WIDTH)
+                    .map(|x| if pixel(c, 1, x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draws_ascii_characters_from_the_table() {
+        assert_eq!(
+            draw('A'),
+            [".###..", "#...#.", "#...#.", "#####.", "#...#.", "#...#.", "#...#.", "......"]
+        );
+        assert_eq!(draw('_')[CELL_HEIGHT - 1], "######");
+        assert!(draw('|').iter().all(|row| row == "..#..."));
+    }
+
+    #[test]
+    fn draws_other_characters_by_shape() {
+        assert_eq!(
+            draw('┌'),
+            ["......", "......", "......", "..####", "..#...", "..#...", "..#...", "..#..."]
+        );
+        assert_eq!(draw('▄')[..4], ["......"; 4]);
+        assert_eq!(draw('▄')[4..], ["######"; 4]);
+        assert_eq!(
+            draw('é'),
+            ["#####.", "#...#.", "#...#.", "#...#.", "#...#.", "#...#.", "#####.", "......"]
+        );
+        assert!(pixel('全', 2, 10, 0) && !pixel('全', 2, 11, 0));
+    }
+}
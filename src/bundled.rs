@@ -0,0 +1,22 @@
+/// Fonts compiled into the binary as `(name, .flf source)` pairs.
+#[cfg(feature = "bundled-fonts")]
+pub const FONTS: &[(&str, &str)] = &[
+    ("banner", include_str!("../fonts/banner.flf")),
+    ("big", include_str!("../fonts/big.flf")),
+    ("block", include_str!("../fonts/block.flf")),
+    ("script", include_str!("../fonts/script.flf")),
+    ("shadow", include_str!("../fonts/shadow.flf")),
+    ("slant", include_str!("../fonts/slant.flf")),
+    ("small", include_str!("../fonts/small.flf")),
+];
+
+/// Fonts compiled into the binary, empty unless the `bundled-fonts` feature is enabled.
+#[cfg(not(feature = "bundled-fonts"))]
+pub const FONTS: &[(&str, &str)] = &[];
+
+pub fn find(name: &str) -> Option<&'static str> {
+    FONTS
+        .iter()
+        .find(|(font, _)| *font == name)
+        .map(|(_, source)| *source)
+}
@@ -0,0 +1,455 @@
+//! Colours for banners, and the ANSI escape sequences that show them in terminals.
+//!
+//! A [`Coloring`] paints every column of a rendered [`Banner`]: in one colour, as a rainbow,
+//! as a linear gradient across or down the banner, or cycling through a palette from one
+//! FIGcharacter to the next. How the colours are written depends on the output format,
+//! terminals get escape sequences of the [`ColorDepth`] they support.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::banner::Banner;
+
+/// A colour given by its red, green and blue components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// The 16 colours of ANSI terminals in the order of their indices, with xterm's values.
+const ANSI: [(&str, Rgb); 16] = [
+    ("black", Rgb::new(0, 0, 0)),
+    ("red", Rgb::new(205, 0, 0)),
+    ("green", Rgb::new(0, 205, 0)),
+    ("yellow", Rgb::new(205, 205, 0)),
+    ("blue", Rgb::new(0, 0, 238)),
+    ("magenta", Rgb::new(205, 0, 205)),
+    ("cyan", Rgb::new(0, 205, 205)),
+    ("white", Rgb::new(229, 229, 229)),
+    ("bright-black", Rgb::new(127, 127, 127)),
+    ("bright-red", Rgb::new(255, 0, 0)),
+    ("bright-green", Rgb::new(0, 255, 0)),
+    ("bright-yellow", Rgb::new(255, 255, 0)),
+    ("bright-blue", Rgb::new(92, 92, 255)),
+    ("bright-magenta", Rgb::new(255, 0, 255)),
+    ("bright-cyan", Rgb::new(0, 255, 255)),
+    ("bright-white", Rgb::new(255, 255, 255)),
+];
+
+/// Names accepted besides those of the ANSI colours.
+const NAMED: [(&str, Rgb); 5] = [
+    ("gray", Rgb::new(127, 127, 127)),
+    ("grey", Rgb::new(127, 127, 127)),
+    ("orange", Rgb::new(255, 165, 0)),
+    ("pink", Rgb::new(255, 192, 203)),
+    ("purple", Rgb::new(128, 0, 128)),
+];
+
+/// Levels of each component in the colour cube of 256-colour terminals.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+
+    /// Fully saturated colour of `hue`, in degrees.
+    pub fn from_hue(hue: f64) -> Rgb {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let rising = (hue.fract() * 255.0).round() as u8;
+        let falling = 255 - rising;
+        match hue as u32 {
+            0 => Rgb::new(255, rising, 0),
+            1 => Rgb::new(falling, 255, 0),
+            2 => Rgb::new(0, 255, rising),
+            3 => Rgb::new(0, falling, 255),
+            4 => Rgb::new(rising, 0, 255),
+            _ => Rgb::new(255, 0, falling),
+        }
+    }
+
+    /// Colour `t` of the way from `self` to `other`.
+    pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        Rgb::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// Escape sequence setting the foreground to this colour, or the closest one available
+    /// at `depth`.
+    pub fn escape(self, depth: ColorDepth) -> String {
+        match depth {
+            ColorDepth::Basic => {
+                let index = closest(self, ANSI.iter().map(|(_, color)| *color));
+                if index < 8 {
+                    format!("\x1b[{}m", 30 + index)
+                } else {
+                    format!("\x1b[{}m", 90 + index - 8)
+                }
+            }
+            ColorDepth::Extended => format!("\x1b[38;5;{}m", self.extended_index()),
+            ColorDepth::TrueColor => format!("\x1b[38;2;{};{};{}m", self.r, self.g, self.b),
+        }
+    }
+
+    /// Index of the closest colour among the colour cube and grey ramp of 256-colour
+    /// terminals.
+    fn extended_index(self) -> usize {
+        let level = |component: u8| closest_level(component);
+        let (r, g, b) = (level(self.r), level(self.g), level(self.b));
+        let cube = Rgb::new(CUBE[r], CUBE[g], CUBE[b]);
+        let average = (self.r as usize + self.g as usize + self.b as usize) / 3;
+        let step = (average.saturating_sub(3) / 10).min(23);
+        let grey = (8 + 10 * step) as u8;
+        if distance(self, Rgb::new(grey, grey, grey)) < distance(self, cube) {
+            232 + step
+        } else {
+            16 + 36 * r + 6 * g + b
+        }
+    }
+}
+
+fn closest_level(component: u8) -> usize {
+    (0..CUBE.len())
+        .min_by_key(|&index| CUBE[index].abs_diff(component))
+        .unwrap_or(0)
+}
+
+/// Index of the colour among `colors` closest to `color`.
+fn closest(color: Rgb, colors: impl Iterator<Item = Rgb>) -> usize {
+    colors
+        .enumerate()
+        .min_by_key(|&(_, candidate)| distance(color, candidate))
+        .map_or(0, |(index, _)| index)
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let component = |a: u8, b: u8| (a.abs_diff(b) as u32).pow(2);
+    component(a.r, b.r) + component(a.g, b.g) + component(a.b, b.b)
+}
+
+impl FromStr for Rgb {
+    type Err = String;
+
+    /// Parses a colour name or a `#RRGGBB` or `#RGB` hex code.
+    fn from_str(s: &str) -> Result<Rgb, String> {
+        let name = s.to_ascii_lowercase();
+        if let Some((_, color)) = ANSI.iter().chain(&NAMED).find(|(known, _)| *known == name) {
+            return Ok(*color);
+        }
+        let invalid = || format!("unknown colour '{}', expected a colour name or #RRGGBB", s);
+        let hex = s.strip_prefix('#').ok_or_else(invalid)?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let component = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match hex.len() {
+            6 => Ok(Rgb::new(
+                component(&hex[0..2])?,
+                component(&hex[2..4])?,
+                component(&hex[4..6])?,
+            )),
+            3 => Ok(Rgb::new(
+                component(&hex[0..1])? * 17,
+                component(&hex[1..2])? * 17,
+                component(&hex[2..3])? * 17,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Formats the colour as a `#rrggbb` hex code.
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Colours a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// The 16 ANSI colours.
+    Basic,
+    /// The 256 colours of xterm.
+    Extended,
+    /// Any 24-bit colour.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Escape sequence resetting the colour.
+    pub const RESET: &'static str = "\x1b[0m";
+
+    /// Colours the terminal described by `$COLORTERM` and `$TERM` supports, `None` when it
+    /// is not known to support any.
+    pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> Option<ColorDepth> {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Some(ColorDepth::TrueColor);
+        }
+        match term {
+            None | Some("" | "dumb") => None,
+            Some(term) if term.ends_with("-direct") || term.ends_with("-truecolor") => {
+                Some(ColorDepth::TrueColor)
+            }
+            Some(term) if term.contains("256color") => Some(ColorDepth::Extended),
+            Some(_) => Some(ColorDepth::Basic),
+        }
+    }
+}
+
+/// How to colour a banner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Coloring {
+    /// Everything in one colour.
+    Solid(Rgb),
+    /// Hues running from red through to magenta, slanted from the top left to the bottom
+    /// right.
+    Rainbow,
+    /// Linear gradient through evenly spread colours, from left to right or from top to
+    /// bottom.
+    Gradient { stops: Vec<Rgb>, vertical: bool },
+    /// Each FIGcharacter in the next colour of the palette, spaces aside.
+    Palette(Vec<Rgb>),
+}
+
+impl FromStr for Coloring {
+    type Err = String;
+
+    /// Parses a colour, `rainbow`, `gradient:FROM:TO`, `vertical-gradient:FROM:TO` or
+    /// `palette:COLOR:COLOR...`, where gradients can have more than two colours.
+    fn from_str(s: &str) -> Result<Coloring, String> {
+        if s == "rainbow" {
+            return Ok(Coloring::Rainbow);
+        }
+        let (kind, colors) = match s.split_once(':') {
+            Some((kind, colors)) => (kind, colors),
+            None => return s.parse().map(Coloring::Solid),
+        };
+        let colors = colors
+            .split(':')
+            .map(str::parse)
+            .collect::<Result<Vec<Rgb>, _>>()?;
+        match kind {
+            "gradient" | "vertical-gradient" if colors.len() < 2 => Err(format!(
+                "'{}' needs at least two colours to blend between",
+                s
+            )),
+            "gradient" | "vertical-gradient" => Ok(Coloring::Gradient {
+                stops: colors,
+                vertical: kind == "vertical-gradient",
+            }),
+            "palette" => Ok(Coloring::Palette(colors)),
+            _ => Err(format!(
+                "unknown colouring '{}', expected a colour, rainbow, gradient:FROM:TO, \
+                 vertical-gradient:FROM:TO or palette:COLOR:COLOR...",
+                s
+            )),
+        }
+    }
+}
+
+impl Coloring {
+    /// Colours of every terminal column of every row of `banner`.
+    pub fn paint(&self, banner: &Banner) -> Vec<Vec<Option<Rgb>>> {
+        let (width, height) = (banner.width(), banner.height());
+        let color = |row: usize, column: usize| match self {
+            Coloring::Solid(color) => Some(*color),
+            Coloring::Rainbow => {
+                let position = (row + column) as f64 / (width + height).max(2) as f64;
+                Some(Rgb::from_hue(300.0 * position))
+            }
+            Coloring::Gradient { stops, vertical } => {
+                let (position, length) = if *vertical {
+                    (row, height)
+                } else {
+                    (column, width)
+                };
+                Some(gradient(
+                    stops,
+                    position as f64 / length.saturating_sub(1).max(1) as f64,
+                ))
+            }
+            Coloring::Palette(_) => None,
+        };
+        let mut colors: Vec<Vec<Option<Rgb>>> = (0..height)
+            .map(|row| (0..width).map(|column| color(row, column)).collect())
+            .collect();
+        if let Coloring::Palette(palette) = self {
+            let drawn = banner
+                .placements
+                .iter()
+                .filter(|placement| placement.code != ' ' as i64);
+            for (placement, color) in drawn.zip(palette.iter().cycle()) {
+                // Clamped to the banner, so a placement reaching past it is cut short.
+                let columns = placement.columns.start.min(width)..placement.columns.end.min(width);
+                let rows = placement.rows.start.min(height)..placement.rows.end.min(height);
+                for row in &mut colors[rows] {
+                    row[columns.clone()].fill(Some(*color));
+                }
+            }
+        }
+        colors
+    }
+}
+
+/// Colour at `t`, between 0 and 1, of a gradient through `stops`.
+fn gradient(stops: &[Rgb], t: f64) -> Rgb {
+    let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let index = (position as usize).min(stops.len() - 2);
+    stops[index].mix(stops[index + 1], position - index as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::{OutputFormat, Placement};
+
+    #[test]
+    fn parses_colours() {
+        assert_eq!("red".parse(), Ok(Rgb::new(205, 0, 0)));
+        assert_eq!("Bright-Blue".parse(), Ok(Rgb::new(92, 92, 255)));
+        assert_eq!("#ff8000".parse(), Ok(Rgb::new(255, 128, 0)));
+        assert_eq!("#f80".parse(), Ok(Rgb::new(255, 136, 0)));
+        assert!("#ff80".parse::<Rgb>().is_err());
+        assert!("#gg0000".parse::<Rgb>().is_err());
+        assert!("chartreuse".parse::<Rgb>().is_err());
+        assert_eq!(Rgb::new(255, 128, 0).to_string(), "#ff8000");
+    }
+
+    #[test]
+    fn parses_colourings() {
+        assert_eq!("rainbow".parse(), Ok(Coloring::Rainbow));
+        assert_eq!("#000".parse(), Ok(Coloring::Solid(Rgb::new(0, 0, 0))));
+        assert_eq!(
+            "vertical-gradient:red:blue".parse(),
+            Ok(Coloring::Gradient {
+                stops: vec![Rgb::new(205, 0, 0), Rgb::new(0, 0, 238)],
+                vertical: true,
+            })
+        );
+        assert_eq!(
+            "palette:red".parse(),
+            Ok(Coloring::Palette(vec![Rgb::new(205, 0, 0)]))
+        );
+        assert!("gradient:red".parse::<Coloring>().is_err());
+        assert!("gradient:red:nope".parse::<Coloring>().is_err());
+        assert!("stripes:red:blue".parse::<Coloring>().is_err());
+    }
+
+    #[test]
+    fn escapes_at_every_depth() {
+        let orange = Rgb::new(255, 128, 0);
+        assert_eq!(orange.escape(ColorDepth::TrueColor), "\x1b[38;2;255;128;0m");
+        assert_eq!(orange.escape(ColorDepth::Extended), "\x1b[38;5;208m");
+        assert_eq!(orange.escape(ColorDepth::Basic), "\x1b[33m");
+        assert_eq!(Rgb::new(0, 255, 255).escape(ColorDepth::Basic), "\x1b[96m");
+        assert_eq!(
+            Rgb::new(128, 128, 128).escape(ColorDepth::Extended),
+            "\x1b[38;5;244m"
+        );
+    }
+
+    #[test]
+    fn detects_terminal_capabilities() {
+        assert_eq!(
+            ColorDepth::detect(Some("truecolor"), Some("xterm")),
+            Some(ColorDepth::TrueColor)
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("xterm-256color")),
+            Some(ColorDepth::Extended)
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("xterm-direct")),
+            Some(ColorDepth::TrueColor)
+        );
+        assert_eq!(
+            ColorDepth::detect(None, Some("vt100")),
+            Some(ColorDepth::Basic)
+        );
+        assert_eq!(ColorDepth::detect(None, Some("dumb")), None);
+        assert_eq!(ColorDepth::detect(None, None), None);
+    }
+
+    #[test]
+    fn paints_gradients_and_palettes() {
+        let banner = Banner {
+            rows: vec!["abc".to_string(), "def".to_string()],
+            text: String::new(),
+            placements: vec![
+                Placement {
+                    index: 0,
+                    code: 'a' as i64,
+                    rows: 0..2,
+                    columns: 0..2,
+                },
+                Placement {
+                    index: 1,
+                    code: ' ' as i64,
+                    rows: 0..2,
+                    columns: 2..2,
+                },
+                Placement {
+                    index: 2,
+                    code: 'b' as i64,
+                    rows: 0..2,
+                    columns: 1..3,
+                },
+            ],
+            colors: Vec::new(),
+            format: OutputFormat::Text,
+        };
+        let (black, white) = (Rgb::new(0, 0, 0), Rgb::new(255, 255, 255));
+        let colors = Coloring::Gradient {
+            stops: vec![black, white],
+            vertical: false,
+        }
+        .paint(&banner);
+        assert_eq!(
+            colors[1],
+            [Some(black), Some(Rgb::new(128, 128, 128)), Some(white)]
+        );
+        let colors = Coloring::Gradient {
+            stops: vec![black, white],
+            vertical: true,
+        }
+        .paint(&banner);
+        assert_eq!(colors[0], [Some(black); 3]);
+        assert_eq!(colors[1], [Some(white); 3]);
+        let colors = Coloring::Palette(vec![black, white]).paint(&banner);
+        assert_eq!(colors[0], [Some(black), Some(white), Some(white)]);
+    }
+
+    #[test]
+    fn cuts_palette_placements_short_at_the_edges() {
+        let banner = Banner {
+            rows: vec!["ab".to_string()],
+            text: String::new(),
+            placements: vec![Placement {
+                index: 0,
+                code: 'a' as i64,
+                rows: 0..2,
+                columns: 1..4,
+            }],
+            colors: Vec::new(),
+            format: OutputFormat::Text,
+        };
+        let white = Rgb::new(255, 255, 255);
+        let colors = Coloring::Palette(vec![white]).paint(&banner);
+        assert_eq!(colors, [[None, Some(white)]]);
+    }
+
+    #[test]
+    fn runs_through_the_hues() {
+        assert_eq!(Rgb::from_hue(0.0), Rgb::new(255, 0, 0));
+        assert_eq!(Rgb::from_hue(120.0), Rgb::new(0, 255, 0));
+        assert_eq!(Rgb::from_hue(270.0), Rgb::new(128, 0, 255));
+        assert_eq!(Rgb::from_hue(360.0), Rgb::new(255, 0, 0));
+    }
+}
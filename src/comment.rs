@@ -0,0 +1,325 @@
+//! Comment output: the banner as a comment block to put at the top of a source file.
+//!
+//! Every row is commented out with the syntax of the chosen language, either with a line
+//! comment marker or inside a block comment, and loses its trailing whitespace so linters
+//! have nothing to complain about. Where a row contains the end of a block comment, or
+//! any `--` in markup comments, its last character is blanked, which cuts a stroke of the
+//! art short rather than ending the comment early or making it invalid. A box of comment
+//! characters can be drawn around the banner.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::banner::{self, Banner};
+
+/// How a language comments out text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// Every line starts with `marker`, which repeats a single character.
+    Line { marker: &'static str },
+    /// Lines between `open` and `close`, each starting with `margin`, which must not
+    /// contain `forbidden`. Boxes are drawn with `fill`.
+    Block {
+        open: &'static str,
+        margin: &'static str,
+        close: &'static str,
+        forbidden: &'static str,
+        fill: char,
+    },
+}
+
+const C_BLOCK: CommentStyle = CommentStyle::Block {
+    open: "/*",
+    margin: " * ",
+    close: " */",
+    forbidden: "*/",
+    fill: '*',
+};
+
+const MARKUP_BLOCK: CommentStyle = CommentStyle::Block {
+    open: "<!--",
+    margin: "",
+    close: "-->",
+    // XML does not allow `--` anywhere in a comment.
+    forbidden: "--",
+    fill: '=',
+};
+
+/// Language names, the first of each being the main one, with their comment style.
+const LANGUAGES: [(&[&str], CommentStyle); 8] = [
+    (
+        &[
+            "rust",
+            "rs",
+            "cpp",
+            "c++",
+            "csharp",
+            "cs",
+            "dart",
+            "go",
+            "java",
+            "javascript",
+            "js",
+            "kotlin",
+            "php",
+            "scala",
+            "swift",
+            "typescript",
+            "ts",
+            "zig",
+        ],
+        CommentStyle::Line { marker: "//" },
+    ),
+    (&["c", "h", "css"], C_BLOCK),
+    (
+        &[
+            "python",
+            "py",
+            "shell",
+            "sh",
+            "bash",
+            "zsh",
+            "fish",
+            "cmake",
+            "dockerfile",
+            "elixir",
+            "julia",
+            "make",
+            "makefile",
+            "nix",
+            "perl",
+            "powershell",
+            "r",
+            "ruby",
+            "rb",
+            "toml",
+            "yaml",
+            "yml",
+        ],
+        CommentStyle::Line { marker: "#" },
+    ),
+    (
+        &["sql", "lua", "ada", "elm", "haskell", "hs"],
+        CommentStyle::Line { marker: "--" },
+    ),
+    (&["html", "xml", "markdown", "md", "svg"], MARKUP_BLOCK),
+    (
+        &["lisp", "asm", "clojure", "ini", "racket", "scheme"],
+        CommentStyle::Line { marker: ";;" },
+    ),
+    (
+        &["tex", "latex", "erlang", "matlab", "prolog"],
+        CommentStyle::Line { marker: "%" },
+    ),
+    (&["vim"], CommentStyle::Line { marker: "\"" }),
+];
+
+impl CommentStyle {
+    /// Comment style of the language called `name`, matched case-insensitively.
+    pub fn for_language(name: &str) -> Option<CommentStyle> {
+        let name = name.to_ascii_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|(names, _)| names.contains(&name.as_str()))
+            .map(|&(_, style)| style)
+    }
+}
+
+/// Parses a language name into its comment style.
+impl FromStr for CommentStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CommentStyle, String> {
+        CommentStyle::for_language(s).ok_or_else(|| {
+            let names: Vec<&str> = LANGUAGES.iter().map(|(names, _)| names[0]).collect();
+            let (last, names) = names.split_last().unwrap();
+            format!(
+                "unknown language '{}', expected one like {} or {}",
+                s,
+                names.join(", "),
+                last
+            )
+        })
+    }
+}
+
+/// Options of the comment output format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentOptions {
+    pub style: CommentStyle,
+    /// Draw a box of comment characters around the banner.
+    pub border: bool,
+}
+
+pub(crate) fn write(
+    banner: &Banner,
+    options: &CommentOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let rows: Vec<String> = banner
+        .rows
+        .iter()
+        .map(|row| defuse(row.trim_end(), options.style))
+        .collect();
+    match (options.style, options.border) {
+        (CommentStyle::Line { marker }, false) => {
+            for row in rows {
+                writeln!(out, "{}", format!("{} {}", marker, row).trim_end())?;
+            }
+        }
+        (CommentStyle::Line { marker }, true) => {
+            let width = banner.width();
+            let edge = marker.chars().rev().collect::<String>();
+            let rule = Fill(
+                marker.chars().next().unwrap_or('#'),
+                width + 2 * marker.len() + 2,
+            );
+            writeln!(out, "{}", rule)?;
+            for row in rows {
+                writeln!(
+                    out,
+                    "{} {}{} {}",
+                    marker,
+                    row,
+                    Fill(' ', width - banner::width(&row)),
+                    edge
+                )?;
+            }
+            writeln!(out, "{}", rule)?;
+        }
+        (
+            CommentStyle::Block {
+                open,
+                margin,
+                close,
+                ..
+            },
+            false,
+        ) => {
+            writeln!(out, "{}", open)?;
+            for row in rows {
+                writeln!(out, "{}", format!("{}{}", margin, row).trim_end())?;
+            }
+            writeln!(out, "{}", close)?;
+        }
+        (
+            CommentStyle::Block {
+                open, close, fill, ..
+            },
+            true,
+        ) => {
+            // The box hangs from the opening delimiter and ends in the closing one.
+            let indent = Fill(' ', open.width() - 1);
+            let close = close.trim_start();
+            let width = indent.1 + 2 + banner.width() + 2;
+            writeln!(out, "{}{}", open, Fill(fill, width - open.width()))?;
+            for row in rows {
+                let padding = Fill(' ', banner.width() - banner::width(&row));
+                writeln!(out, "{}{} {}{} {}", indent, fill, row, padding, fill)?;
+            }
+            writeln!(
+                out,
+                "{}{}{}",
+                indent,
+                Fill(fill, width - indent.1 - close.width()),
+                close
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Blanks the last character of every sequence in `row` that a block comment must not
+/// contain.
+fn defuse(row: &str, style: CommentStyle) -> String {
+    let CommentStyle::Block { forbidden, .. } = style else {
+        return row.to_string();
+    };
+    let mut row = row.to_string();
+    while let Some(start) = row.find(forbidden) {
+        let last = start + forbidden.len() - 1;
+        row.replace_range(last..last + 1, " ");
+    }
+    row
+}
+
+/// A character repeated a number of times.
+struct Fill(char, usize);
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for _ in 0..self.1 {
+            write!(f, "{}", self.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::OutputFormat;
+
+    fn comment(rows: &[&str], language: &str, border: bool) -> String {
+        let banner = Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: Vec::new(),
+            colors: Vec::new(),
+            format: OutputFormat::Comment(CommentOptions {
+                style: language.parse().unwrap(),
+                border,
+            }),
+        };
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn comments_out_rows_without_trailing_whitespace() {
+        let rows = [" _  ", "|_| ", "    "];
+        assert_eq!(comment(&rows, "rust", false), "//  _\n// |_|\n//\n");
+        assert_eq!(comment(&rows, "Python", false), "#  _\n# |_|\n#\n");
+        assert_eq!(comment(&rows, "c", false), "/*\n *  _\n * |_|\n *\n */\n");
+        assert_eq!(comment(&rows, "html", false), "<!--\n _\n|_|\n\n-->\n");
+    }
+
+    #[test]
+    fn draws_boxes_of_comment_characters() {
+        let rows = [" _ ", "|_|"];
+        assert_eq!(
+            comment(&rows, "sql", true),
+            "---------\n--  _  --\n-- |_| --\n---------\n"
+        );
+        assert_eq!(
+            comment(&rows, "c", true),
+            "/*******\n *  _  *\n * |_| *\n ******/\n"
+        );
+        assert_eq!(
+            comment(&rows, "xml", true),
+            "<!--======\n   =  _  =\n   = |_| =\n   ====-->\n"
+        );
+    }
+
+    #[test]
+    fn keeps_block_comments_open() {
+        assert_eq!(comment(&["/*/ -->"], "css", false), "/*\n * /*  -->\n */\n");
+        assert_eq!(defuse("<!-- -->", MARKUP_BLOCK), "<!-  - >");
+        assert!("cobol".parse::<CommentStyle>().is_err());
+    }
+
+    #[test]
+    fn keeps_double_hyphens_out_of_markup_comments() {
+        let rows = [" -- ", "|-->", "---"];
+        assert_eq!(comment(&rows, "xml", false), "<!--\n -\n|- >\n- -\n-->\n");
+        assert_eq!(
+            comment(&rows, "xml", true),
+            "<!--=======\n   =  -   =\n   = |- > =\n   = - -  =\n   =====-->\n"
+        );
+        assert_eq!(comment(&["a--b"], "c", false), "/*\n * a--b\n */\n");
+    }
+}
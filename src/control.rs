@@ -0,0 +1,641 @@
+//! FIGlet control files (`.flc`).
+//!
+//! Control files translate input characters before they are looked up in the font and
+//! choose how the input bytes are decoded into characters. Every line holds one command:
+//!
+//! - `t in out` translates the character `in` to `out`, `t a-z A-Z` a whole range
+//! - `in out` with two numbers is a short form of `t`
+//! - `f` freezes the translations so far: later ones apply to their result
+//! - `u`, `h`, `j` and `b` read the input as UTF-8, HZ, Shift-JIS or DBCS
+//! - `g0` to `g3` followed by `94`, `96` or `94x94` and a final character designate an
+//!   ISO 2022 character set, `gL` and `gR` followed by `0` to `3` invoke one into the left
+//!   or right half, and select ISO 2022 input
+//!
+//! Lines starting with `#` are comments. Characters are given as themselves or with a
+//! backslash as `\n`-style escapes or numbers in decimal, octal (`\0101`) or hex (`\0x41`).
+//!
+//! Unlike figlet, which reads ISO 2022 (that is Latin-1) unless told otherwise, input is
+//! read as UTF-8 until a control file selects another mode.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::{self, Utf8Error};
+
+use crate::archive;
+use crate::error::FigctlError;
+use crate::figfont::{self, ParseError};
+use crate::font;
+use crate::search::SearchPath;
+
+/// Resolves a control file given on the command line: `spec` is a path when it points to an
+/// existing file or contains a path separator, otherwise it is looked up as `<spec>.flc` on
+/// the `search_path`. When that fails too it is returned as a path, for reading it to
+/// report the problem.
+pub fn find(spec: &str, search_path: &SearchPath) -> PathBuf {
+    let path = Path::new(spec);
+    if path.is_file() || spec.contains(std::path::is_separator) {
+        return path.to_path_buf();
+    }
+    search_path
+        .find_control(spec)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// How input bytes are decoded into character codes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputMode {
+    #[default]
+    Utf8,
+    /// ISO 2022 with the designated character sets.
+    Iso2022,
+    /// Bytes from 0x80 up start a two-byte character.
+    Dbcs,
+    ShiftJis,
+    /// `~{` switches to two-byte GB 2312 characters and `~}` back to ASCII.
+    Hz,
+}
+
+/// An ISO 2022 character set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Charset {
+    /// Added to the 7-bit character to get its code: 0 for ASCII, 0x80 for the right half
+    /// of Latin-1 and the final character of the designation times 65536 for anything
+    /// else, plus 0x80 for 96-character sets.
+    base: i64,
+    /// 96-character sets also use the positions of space and delete.
+    size96: bool,
+    /// 94x94 sets take two bytes per character.
+    double: bool,
+}
+
+const ASCII: Charset = Charset {
+    base: 0,
+    size96: false,
+    double: false,
+};
+
+const LATIN1: Charset = Charset {
+    base: 0x80,
+    size96: true,
+    double: false,
+};
+
+impl Charset {
+    fn new(final_byte: u8, size96: bool, double: bool) -> Charset {
+        match (final_byte, size96, double) {
+            (b'B', false, false) => ASCII,
+            (b'A', true, false) => LATIN1,
+            _ => Charset {
+                base: (final_byte as i64) << 16 | if size96 { 0x80 } else { 0 },
+                size96,
+                double,
+            },
+        }
+    }
+}
+
+/// A translation of the characters `low..=high` by `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Translation {
+    low: i64,
+    high: i64,
+    offset: i64,
+}
+
+/// The combined effect of any number of control files, applied in the order they were
+/// read.
+#[derive(Debug, Clone)]
+pub struct Control {
+    mode: InputMode,
+    /// Translations split at every freeze and at the start of every file. Within a stage
+    /// the first matching translation applies, and every stage works on the result of
+    /// the previous one.
+    stages: Vec<Vec<Translation>>,
+    /// Character sets designated to G0 to G3.
+    charsets: [Charset; 4],
+    /// Sets invoked into the left (GL) and right (GR) half of the byte range.
+    left: usize,
+    right: usize,
+}
+
+impl Default for Control {
+    fn default() -> Control {
+        Control {
+            mode: InputMode::Utf8,
+            stages: Vec::new(),
+            charsets: [ASCII, LATIN1, ASCII, ASCII],
+            left: 0,
+            right: 1,
+        }
+    }
+}
+
+impl Control {
+    pub fn new() -> Control {
+        Control::default()
+    }
+
+    pub fn mode(&self) -> InputMode {
+        self.mode
+    }
+
+    /// Reads the control file at `path`, which may be compressed like a font.
+    pub fn load(&mut self, path: &Path) -> Result<(), FigctlError> {
+        let content = fs::read(path).map_err(|source| FigctlError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        // As with fonts, a damaged archive is malformed rather than unreadable.
+        let content = archive::unpack(content).map_err(|err| FigctlError::ControlParse {
+            file: path.to_path_buf(),
+            line: 1,
+            column: 1,
+            message: format!("corrupt archive: {}", err),
+        })?;
+        self.read(&font::decode(content))
+            .map_err(|err| FigctlError::ControlParse {
+                file: path.to_path_buf(),
+                line: err.line,
+                column: err.column,
+                message: err.message,
+            })
+    }
+
+    /// Adds the commands of a control file to the ones read so far.
+    pub fn read(&mut self, source: &str) -> Result<(), ParseError> {
+        self.stages.push(Vec::new());
+        for (index, line) in source.lines().enumerate() {
+            let error = |column: usize, message: &str| ParseError {
+                line: index + 1,
+                column,
+                message: message.to_string(),
+            };
+            let mut cursor = Cursor::new(line);
+            cursor.skip_whitespace();
+            match cursor.peek() {
+                None | Some('#') => {}
+                // The `flc2a` signature looks like a freeze and is one, like in figlet.
+                Some('f') => self.stages.push(Vec::new()),
+                Some('t') => {
+                    cursor.next();
+                    cursor.skip_whitespace();
+                    let column = cursor.column();
+                    let low = cursor.character().map_err(|(c, m)| error(c, &m))?;
+                    let high = if cursor.peek() == Some('-') {
+                        cursor.next();
+                        cursor.character().map_err(|(c, m)| error(c, &m))?
+                    } else {
+                        low
+                    };
+                    cursor.skip_whitespace();
+                    let to = cursor.character().map_err(|(c, m)| error(c, &m))?;
+                    if high < low {
+                        return Err(error(column, "translation range ends before it starts"));
+                    }
+                    self.translate(low, high, to);
+                }
+                Some(c) if c.is_ascii_digit() || c == '-' => {
+                    let from = cursor.number().map_err(|(c, m)| error(c, &m))?;
+                    cursor.skip_whitespace();
+                    let to = cursor.number().map_err(|(c, m)| error(c, &m))?;
+                    self.translate(from, from, to);
+                }
+                Some('u') => self.mode = InputMode::Utf8,
+                Some('h') => self.mode = InputMode::Hz,
+                Some('j') => self.mode = InputMode::ShiftJis,
+                Some('b') => self.mode = InputMode::Dbcs,
+                Some('g') => {
+                    cursor.next();
+                    self.designate(&mut cursor).map_err(|(c, m)| error(c, &m))?;
+                    self.mode = InputMode::Iso2022;
+                }
+                Some(c) => return Err(error(cursor.column(), &format!("unknown command '{}'", c))),
+            }
+        }
+        Ok(())
+    }
+
+    fn translate(&mut self, low: i64, high: i64, to: i64) {
+        let translation = Translation {
+            low,
+            high,
+            offset: to - low,
+        };
+        match self.stages.last_mut() {
+            Some(stage) => stage.push(translation),
+            None => self.stages.push(vec![translation]),
+        }
+    }
+
+    /// Handles the rest of a `g` command.
+    fn designate(&mut self, cursor: &mut Cursor) -> Result<(), (usize, String)> {
+        let column = cursor.column();
+        match cursor.next() {
+            Some(half @ ('L' | 'l' | 'R' | 'r')) => {
+                cursor.skip_whitespace();
+                let column = cursor.column();
+                let set = cursor
+                    .next()
+                    .and_then(|c| c.to_digit(10))
+                    .filter(|&set| set < 4)
+                    .ok_or_else(|| (column, "expected a G set from 0 to 3".to_string()))?;
+                if half.eq_ignore_ascii_case(&'L') {
+                    self.left = set as usize;
+                } else {
+                    self.right = set as usize;
+                }
+                Ok(())
+            }
+            Some(set @ '0'..='3') => {
+                cursor.skip_whitespace();
+                let column = cursor.column();
+                let (size96, double) = match cursor.word() {
+                    "94" => (false, false),
+                    "96" => (true, false),
+                    "94x94" => (false, true),
+                    _ => return Err((column, "expected 94, 96 or 94x94".to_string())),
+                };
+                cursor.skip_whitespace();
+                let column = cursor.column();
+                let final_byte = cursor
+                    .next()
+                    .filter(|c| ('0'..='~').contains(c))
+                    .ok_or_else(|| (column, "expected the final character of the set".into()))?;
+                let set = set as usize - '0' as usize;
+                self.charsets[set] = Charset::new(final_byte as u8, size96, double);
+                Ok(())
+            }
+            _ => Err((column, "expected 0 to 3, L or R after g".to_string())),
+        }
+    }
+
+    /// Translates a character code through every stage.
+    pub fn map(&self, code: i64) -> i64 {
+        self.stages.iter().fold(code, |code, stage| {
+            match stage.iter().find(|t| (t.low..=t.high).contains(&code)) {
+                Some(translation) => code + translation.offset,
+                None => code,
+            }
+        })
+    }
+
+    /// Decodes input into character codes according to the input mode. Only UTF-8 input
+    /// can be invalid; the other modes take bytes as they come, like figlet.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<i64>, Utf8Error> {
+        Ok(match self.mode {
+            InputMode::Utf8 => str::from_utf8(bytes)?.chars().map(|c| c as i64).collect(),
+            InputMode::Iso2022 => self.decode_iso2022(bytes),
+            InputMode::Dbcs => decode_double(bytes, |b| b >= 0x80),
+            InputMode::ShiftJis => decode_double(bytes, |b| {
+                (0x81..=0x9f).contains(&b) || (0xe0..=0xef).contains(&b)
+            }),
+            InputMode::Hz => decode_hz(bytes),
+        })
+    }
+
+    fn decode_iso2022(&self, bytes: &[u8]) -> Vec<i64> {
+        const ESC: u8 = 0x1b;
+        const SO: u8 = 0x0e;
+        const SI: u8 = 0x0f;
+        const SS2: u8 = 0x8e;
+        const SS3: u8 = 0x8f;
+
+        let mut charsets = self.charsets;
+        let (mut left, mut right) = (self.left, self.right);
+        let mut single_shift = None;
+        let mut codes = Vec::with_capacity(bytes.len());
+        let mut bytes = bytes.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                ESC => {
+                    let Some(command) = bytes.next() else { break };
+                    match command {
+                        b'N' => single_shift = Some(2),
+                        b'O' => single_shift = Some(3),
+                        b'n' => left = 2,
+                        b'o' => left = 3,
+                        b'~' => right = 1,
+                        b'}' => right = 2,
+                        b'|' => right = 3,
+                        b'(' | b')' | b'*' | b'+' | b'-' | b'.' | b'/' => {
+                            let set = match command {
+                                b'(' => 0,
+                                b')' | b'-' => 1,
+                                b'*' | b'.' => 2,
+                                _ => 3,
+                            };
+                            if let Some(final_byte) = bytes.next() {
+                                let size96 = matches!(command, b'-' | b'.' | b'/');
+                                charsets[set] = Charset::new(final_byte, size96, false);
+                            }
+                        }
+                        b'$' => {
+                            // ESC $ F is an old form of ESC $ ( F.
+                            let set = match bytes.peek() {
+                                Some(b'(') => 0,
+                                Some(b')') => 1,
+                                Some(b'*') => 2,
+                                Some(b'+') => 3,
+                                _ => {
+                                    if let Some(final_byte) = bytes.next() {
+                                        charsets[0] = Charset::new(final_byte, false, true);
+                                    }
+                                    continue;
+                                }
+                            };
+                            bytes.next();
+                            if let Some(final_byte) = bytes.next() {
+                                charsets[set] = Charset::new(final_byte, false, true);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                SO => left = 1,
+                SI => left = 0,
+                SS2 => single_shift = Some(2),
+                SS3 => single_shift = Some(3),
+                _ => {
+                    let (set, low) = match byte {
+                        0x20..=0x7f => (left, byte),
+                        0xa0..=0xff => (right, byte & 0x7f),
+                        _ => {
+                            codes.push(byte as i64);
+                            continue;
+                        }
+                    };
+                    let charset = charsets[single_shift.take().unwrap_or(set)];
+                    if !charset.size96 && (low == 0x20 || low == 0x7f) {
+                        codes.push(low as i64);
+                    } else if charset.double {
+                        let Some(second) = bytes.next() else { break };
+                        codes.push(charset.base | (low as i64) << 8 | (second & 0x7f) as i64);
+                    } else {
+                        codes.push(charset.base | low as i64);
+                    }
+                }
+            }
+        }
+        codes
+    }
+}
+
+/// Decodes input where bytes for which `lead` holds start a two-byte character, whose
+/// code is both bytes together.
+fn decode_double(bytes: &[u8], lead: impl Fn(u8) -> bool) -> Vec<i64> {
+    let mut codes = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter().copied();
+    while let Some(byte) = bytes.next() {
+        if lead(byte) {
+            let Some(second) = bytes.next() else { break };
+            codes.push((byte as i64) << 8 | second as i64);
+        } else {
+            codes.push(byte as i64);
+        }
+    }
+    codes
+}
+
+/// Decodes HZ, giving GB 2312 characters their EUC-CN codes like figlet does.
+fn decode_hz(bytes: &[u8]) -> Vec<i64> {
+    let mut codes = Vec::with_capacity(bytes.len());
+    let mut gb = false;
+    let mut bytes = bytes.iter().copied();
+    while let Some(byte) = bytes.next() {
+        if byte == b'~' {
+            match bytes.next() {
+                Some(b'{') => gb = true,
+                Some(b'}') => gb = false,
+                Some(b'~') => codes.push(b'~' as i64),
+                // A line continuation.
+                Some(b'\n') => {}
+                Some(other) => codes.extend([b'~' as i64, other as i64]),
+                None => codes.push(b'~' as i64),
+            }
+        } else if gb && byte > 0x20 && byte < 0x7f {
+            let Some(second) = bytes.next() else { break };
+            codes.push(((byte as i64) << 8 | second as i64) | 0x8080);
+        } else {
+            codes.push(byte as i64);
+        }
+    }
+    codes
+}
+
+/// Reads a line of a control file, keeping track of the column for error reporting.
+struct Cursor<'a> {
+    rest: &'a str,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(line: &'a str) -> Cursor<'a> {
+        Cursor {
+            rest: line,
+            column: 1,
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.column
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        self.column += 1;
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// Reads up to the next whitespace.
+    fn word(&mut self) -> &'a str {
+        let end = self
+            .rest
+            .find(char::is_whitespace)
+            .unwrap_or(self.rest.len());
+        let word = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        self.column += word.chars().count();
+        word
+    }
+
+    /// Reads a number in decimal, octal or hex, up to the next whitespace or `-` that
+    /// follows it.
+    fn number(&mut self) -> Result<i64, (usize, String)> {
+        let column = self.column;
+        let sign = if self.peek() == Some('-') {
+            self.next();
+            "-"
+        } else {
+            ""
+        };
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(self.rest.len());
+        let digits = &self.rest[..end];
+        self.rest = &self.rest[end..];
+        self.column += end;
+        figfont::parse_code(&format!("{}{}", sign, digits))
+            .ok_or_else(|| (column, format!("invalid number '{}{}'", sign, digits)))
+    }
+
+    /// Reads a character given as itself or as a backslash escape.
+    fn character(&mut self) -> Result<i64, (usize, String)> {
+        let column = self.column;
+        match self.next() {
+            None => Err((column, "expected a character".to_string())),
+            Some('\\') => match self.peek() {
+                Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+                Some(c) => {
+                    self.next();
+                    Ok(match c {
+                        'a' => 0x07,
+                        'b' => 0x08,
+                        'e' => 0x1b,
+                        'f' => 0x0c,
+                        'n' => 0x0a,
+                        'r' => 0x0d,
+                        't' => 0x09,
+                        'v' => 0x0b,
+                        c => c as i64,
+                    })
+                }
+                None => Err((column, "expected a character after '\\'".to_string())),
+            },
+            Some(c) => Ok(c as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control(sources: &[&str]) -> Control {
+        let mut control = Control::new();
+        for source in sources {
+            control.read(source).unwrap();
+        }
+        control
+    }
+
+    fn error(source: &str) -> (usize, usize, String) {
+        let err = Control::new().read(source).unwrap_err();
+        (err.line, err.column, err.message)
+    }
+
+    #[test]
+    fn translates_characters_and_ranges() {
+        let control = control(&["flc2a\n# upper case\nt a-z A-Z\nt é e\n0xe8 101\n"]);
+        assert_eq!(control.map('q' as i64), 'Q' as i64);
+        assert_eq!(control.map('é' as i64), 'e' as i64);
+        assert_eq!(control.map(0xe8), 'e' as i64);
+        assert_eq!(control.map('!' as i64), '!' as i64);
+    }
+
+    #[test]
+    fn reads_escapes() {
+        let control = control(&["t \\  _\nt \\0x41 \\0102\nt \\\\ /\nt \\t \\-2\n"]);
+        assert_eq!(control.map(' ' as i64), '_' as i64);
+        assert_eq!(control.map('A' as i64), 'B' as i64);
+        assert_eq!(control.map('\\' as i64), '/' as i64);
+        assert_eq!(control.map('\t' as i64), -2);
+    }
+
+    #[test]
+    fn first_translation_of_a_stage_wins() {
+        let control = control(&["t a b\nt a c\nt b d\n"]);
+        assert_eq!(control.map('a' as i64), 'b' as i64);
+        assert_eq!(control.map('b' as i64), 'd' as i64);
+    }
+
+    #[test]
+    fn freezes_and_files_start_new_stages() {
+        let frozen = control(&["t a b\nf\nt b c\n"]);
+        assert_eq!(frozen.map('a' as i64), 'c' as i64);
+        let files = control(&["t a b\n", "t b c\n"]);
+        assert_eq!(files.map('a' as i64), 'c' as i64);
+    }
+
+    #[test]
+    fn selects_input_modes() {
+        assert_eq!(Control::new().mode(), InputMode::Utf8);
+        assert_eq!(control(&["j\n"]).mode(), InputMode::ShiftJis);
+        assert_eq!(control(&["b\n"]).mode(), InputMode::Dbcs);
+        assert_eq!(control(&["h\n"]).mode(), InputMode::Hz);
+        assert_eq!(control(&["j\nu\n"]).mode(), InputMode::Utf8);
+        assert_eq!(control(&["g1 96 A\n"]).mode(), InputMode::Iso2022);
+    }
+
+    #[test]
+    fn decodes_utf8() {
+        assert_eq!(Control::new().decode("aé".as_bytes()), Ok(vec![0x61, 0xe9]));
+        assert!(Control::new().decode(b"a\xe9").is_err());
+    }
+
+    #[test]
+    fn decodes_iso2022() {
+        let latin1 = control(&["g0 94 B\n"]);
+        assert_eq!(latin1.decode(b"a\xe9"), Ok(vec![0x61, 0xe9]));
+        // ISO 8859-2 into G1, invoked into the left half.
+        let latin2 = control(&["g1 96 B\ngL 1\n"]);
+        assert_eq!(
+            latin2.decode(b"\x21"),
+            Ok(vec![(b'B' as i64) << 16 | 0x80 | 0x21])
+        );
+        // JIS X 0208 designated by an escape sequence, then ASCII again.
+        let codes = latin1.decode(b"\x1b$B\x30\x21\x1b(Ba").unwrap();
+        assert_eq!(codes, [(b'B' as i64) << 16 | 0x3021, 'a' as i64]);
+        let codes = latin1.decode(b"\x1b(Jx").unwrap();
+        assert_eq!(codes, [(b'J' as i64) << 16 | 'x' as i64]);
+    }
+
+    #[test]
+    fn decodes_double_byte_encodings() {
+        assert_eq!(
+            control(&["j\n"]).decode(b"a\x82\xa0\xb1"),
+            Ok(vec![0x61, 0x82a0, 0xb1])
+        );
+        assert_eq!(
+            control(&["b\n"]).decode(b"a\xb0\xa1"),
+            Ok(vec![0x61, 0xb0a1])
+        );
+        assert_eq!(
+            control(&["h\n"]).decode(b"a~{\x30\x21~}~~"),
+            Ok(vec![0x61, 0xb0a1, '~' as i64])
+        );
+    }
+
+    #[test]
+    fn reports_the_position_of_errors() {
+        assert_eq!(
+            error("t a-z A-Z\nt z-a A\n"),
+            (2, 3, "translation range ends before it starts".to_string())
+        );
+        assert_eq!(error("t a\n"), (1, 4, "expected a character".to_string()));
+        assert_eq!(
+            error("65 0xZZ\n"),
+            (1, 4, "invalid number '0xZZ'".to_string())
+        );
+        assert_eq!(
+            error("g4 94 B\n"),
+            (1, 2, "expected 0 to 3, L or R after g".to_string())
+        );
+        assert_eq!(
+            error("g0 95 B\n"),
+            (1, 4, "expected 94, 96 or 94x94".to_string())
+        );
+        assert_eq!(error("  x\n"), (1, 3, "unknown command 'x'".to_string()));
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::Utf8Error;
+
+/// Everything that can make figctl fail.
+///
+/// Each kind of error exits with its own status so wrapper scripts can tell them apart:
+///
+/// | status | meaning                                         |
+/// |--------|-------------------------------------------------|
+/// | 0      | success                                         |
+/// | 2      | invalid command line (reported by clap)         |
+/// | 3      | font not found                                  |
+/// | 4      | font or control file is malformed               |
+/// | 5      | message contains a character the font lacks     |
+/// | 6      | a file could not be read or input is not UTF-8  |
+/// | 7      | the output could not be written                 |
+#[derive(Debug)]
+pub enum FigctlError {
+    FontNotFound {
+        name: String,
+        searched: Vec<PathBuf>,
+    },
+    FontParse {
+        /// Path or built-in name of the font.
+        font: String,
+        /// 1-based line and column the problem was found at.
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    ControlParse {
+        file: PathBuf,
+        /// 1-based line and column the problem was found at.
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    UnsupportedCharacter {
+        /// Character code after decoding and translation by control files, which is not
+        /// always a Unicode scalar value.
+        code: i64,
+        font: String,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The message is not valid UTF-8 and no control file selected another encoding.
+    Encoding(Utf8Error),
+    Output(io::Error),
+}
+
+impl FigctlError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FigctlError::FontNotFound { .. } => 3,
+            FigctlError::FontParse { .. } | FigctlError::ControlParse { .. } => 4,
+            FigctlError::UnsupportedCharacter { .. } => 5,
+            FigctlError::Io { .. } | FigctlError::Encoding(_) => 6,
+            FigctlError::Output(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for FigctlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FigctlError::FontNotFound { name, searched } => {
+                write!(f, "font '{}' not found (searched ", name)?;
+                for (i, dir) in searched.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", dir.display())?;
+                }
+                write!(f, ")")
+            }
+            FigctlError::FontParse {
+                font,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "malformed font {}, line {}, column {}: {}",
+                font, line, column, message
+            ),
+            FigctlError::ControlParse {
+                file,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "malformed control file {}, line {}, column {}: {}",
+                file.display(),
+                line,
+                column,
+                message
+            ),
+            FigctlError::UnsupportedCharacter { code, font } => {
+                match u32::try_from(*code).ok().and_then(char::from_u32) {
+                    Some(character) => write!(
+                        f,
+                        "font {} has no character {:?} (U+{:04X})",
+                        font, character, code
+                    ),
+                    None if *code < 0 => write!(f, "font {} has no character {}", font, code),
+                    None => write!(f, "font {} has no character 0x{:X}", font, code),
+                }
+            }
+            FigctlError::Io { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            FigctlError::Encoding(source) => write!(
+                f,
+                "input is not valid UTF-8 ({}), use a control file to read other encodings",
+                source
+            ),
+            FigctlError::Output(source) => write!(f, "cannot write output: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for FigctlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FigctlError::Io { source, .. } | FigctlError::Output(source) => Some(source),
+            FigctlError::Encoding(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_failures_in_one_line() {
+        let err = FigctlError::FontParse {
+            font: "broken.flf".to_string(),
+            line: 3,
+            column: 1,
+            message: "missing end mark".to_string(),
+        };
+        assert_eq!(err.exit_code(), 4);
+        assert!(!err.to_string().contains('\n'));
+        let err = FigctlError::UnsupportedCharacter {
+            code: 'é' as i64,
+            font: "standard".to_string(),
+        };
+        assert_eq!(err.exit_code(), 5);
+        assert_eq!(
+            err.to_string(),
+            "font standard has no character 'é' (U+00E9)"
+        );
+        let err = FigctlError::UnsupportedCharacter {
+            code: 0x423021,
+            font: "standard".to_string(),
+        };
+        assert_eq!(err.to_string(), "font standard has no character 0x423021");
+        let err = FigctlError::Output(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert_eq!(err.exit_code(), 7);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
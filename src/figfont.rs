@@ -0,0 +1,355 @@
+//! Parser for FIGfont 2.2 (`.flf`) files and TOIlet (`.tlf`) fonts.
+//!
+//! A FIGfont starts with a header line, followed by comment lines, the 102 required
+//! characters (ASCII 32 to 126 and the seven Deutsch characters) and any number of
+//! code-tagged characters. Every character is `height` rows, each ending in an endmark.
+//!
+//! TOIlet fonts share the FIGfont format apart from their `tlf2a` signature, and are
+//! always UTF-8, so their sub-characters may be any Unicode character.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Unicode code points of the Deutsch characters, in the order they follow ASCII 126:
+/// Ä, Ö, Ü, ä, ö, ü and ß.
+const DEUTSCH: [i64; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+/// Number of characters every font has to define before its code-tagged characters.
+pub const REQUIRED_CHARACTERS: usize = 95 + DEUTSCH.len();
+
+/// Tallest FIGcharacters a font may have, far beyond any real font, so that a damaged
+/// header is rejected instead of making rendering allocate rows without end.
+pub const MAX_HEIGHT: usize = 1000;
+
+/// Parameters from the header line of a font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Sub-character that is drawn as a blank but never smushed.
+    pub hardblank: char,
+    /// Number of rows of every character.
+    pub height: usize,
+    /// Rows from the top of a character to its baseline.
+    pub baseline: usize,
+    pub max_length: usize,
+    pub old_layout: i32,
+    pub comment_lines: usize,
+    /// 0 for left-to-right, 1 for right-to-left.
+    pub print_direction: Option<i32>,
+    pub full_layout: Option<i32>,
+    pub codetag_count: Option<usize>,
+}
+
+/// A parsed font. Rows keep their hardblanks, only the endmarks are removed.
+#[derive(Debug, Clone)]
+pub struct FigFont {
+    pub header: Header,
+    pub comments: String,
+    /// Rows of every character by code point; negative codes are allowed by the format
+    /// but never rendered.
+    pub characters: HashMap<i64, Vec<String>>,
+}
+
+/// A problem found while parsing a font, with the 1-based line and column it is at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FigFont {
+    pub fn parse(source: &str) -> Result<FigFont, ParseError> {
+        let mut lines = Lines::new(source);
+        let header = parse_header(lines.next().unwrap_or_default())?;
+        let mut comments = Vec::new();
+        for _ in 0..header.comment_lines {
+            match lines.next() {
+                Some(line) => comments.push(line),
+                None => return Err(lines.end("font ends inside its comment block")),
+            }
+        }
+
+        let mut characters = HashMap::new();
+        let required = (32..127).chain(DEUTSCH);
+        for (index, code) in required.enumerate() {
+            if lines.at_end() {
+                return Err(lines.end(&format!(
+                    "font ends after {} of the {} required characters",
+                    index, REQUIRED_CHARACTERS
+                )));
+            }
+            characters.insert(code, lines.character(header.height)?);
+        }
+        while let Some(line) = lines.next() {
+            let Some((column, tag)) = fields(line, 1).next() else {
+                continue;
+            };
+            let code = parse_code(tag)
+                .ok_or_else(|| lines.error(column, &format!("invalid character code '{}'", tag)))?;
+            if code == -1 {
+                return Err(lines.error(column, "character code -1 is reserved"));
+            }
+            characters.insert(code, lines.character(header.height)?);
+        }
+
+        Ok(FigFont {
+            header,
+            comments: comments.join("\n"),
+            characters,
+        })
+    }
+
+    /// Rows of `character`, if the font defines it.
+    pub fn character(&self, character: char) -> Option<&[String]> {
+        self.characters.get(&(character as i64)).map(Vec::as_slice)
+    }
+}
+
+/// Lines of a font, keeping track of the line number for error reporting.
+struct Lines<'a> {
+    lines: Vec<&'a str>,
+    /// Number of lines consumed so far, which is the 1-based number of the last one.
+    position: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let line = self.lines.get(self.position).copied()?;
+        self.position += 1;
+        Some(line)
+    }
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Lines<'a> {
+        Lines {
+            lines: source.lines().collect(),
+            position: 0,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.lines.len()
+    }
+
+    /// An error at `column` of the line returned last.
+    fn error(&self, column: usize, message: &str) -> ParseError {
+        ParseError {
+            line: self.position.max(1),
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    /// An error at the very end of the font.
+    fn end(&self, message: &str) -> ParseError {
+        let last = self.lines.last().copied().unwrap_or_default();
+        ParseError {
+            line: self.lines.len().max(1),
+            column: last.chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    /// Reads the `height` rows of a character, removing their endmarks.
+    ///
+    /// Like figlet, trailing whitespace is ignored and the endmark is the last character
+    /// left, removed together with every copy of it directly before it.
+    fn character(&mut self, height: usize) -> Result<Vec<String>, ParseError> {
+        let mut rows = Vec::new();
+        for row in 0..height {
+            let line = self.next().ok_or_else(|| {
+                self.end(&format!(
+                    "font ends inside a character, after {} of its {} rows",
+                    row, height
+                ))
+            })?;
+            let line = line.trim_end();
+            let endmark = line
+                .chars()
+                .next_back()
+                .ok_or_else(|| self.error(1, "character row without an endmark"))?;
+            rows.push(line.trim_end_matches(endmark).to_string());
+        }
+        Ok(rows)
+    }
+}
+
+fn parse_header(line: &str) -> Result<Header, ParseError> {
+    let error = |column, message: String| ParseError {
+        line: 1,
+        column,
+        message,
+    };
+    if !(line.starts_with("flf2") || line.starts_with("tlf2")) || line.chars().count() < 5 {
+        return Err(error(1, "missing flf2a or tlf2a signature".to_string()));
+    }
+    let mut chars = line.char_indices().skip(5);
+    let (offset, hardblank) = chars
+        .next()
+        .filter(|(_, c)| !c.is_whitespace())
+        .ok_or_else(|| error(6, "missing hardblank".to_string()))?;
+    let rest = offset + hardblank.len_utf8();
+
+    let mut params = fields(&line[rest..], line[..rest].chars().count() + 1);
+    let mut number = |field: &str, range: (i64, i64), required: bool| {
+        let Some((column, param)) = params.next() else {
+            if required {
+                let column = line.chars().count() + 1;
+                return Err(error(column, format!("missing {} in header", field)));
+            }
+            return Ok(None);
+        };
+        let value = param
+            .parse::<i64>()
+            .map_err(|_| error(column, format!("invalid {} '{}' in header", field, param)))?;
+        if value < range.0 || value > range.1 {
+            return Err(error(
+                column,
+                format!(
+                    "{} must be between {} and {}, got {}",
+                    field, range.0, range.1, value
+                ),
+            ));
+        }
+        Ok(Some(value))
+    };
+    let count = (0, i32::MAX as i64);
+    let height = number("height", (1, MAX_HEIGHT as i64), true)?.unwrap_or_default();
+    let baseline = number("baseline", (0, height), true)?.unwrap_or_default();
+    let max_length = number("max length", count, true)?.unwrap_or_default();
+    let old_layout = number("old layout", (-1, 63), true)?.unwrap_or_default();
+    let comment_lines = number("comment line count", count, true)?.unwrap_or_default();
+    let print_direction = number("print direction", (0, 1), false)?;
+    let full_layout = number("full layout", (0, 32767), false)?;
+    let codetag_count = number("codetag count", count, false)?;
+    Ok(Header {
+        hardblank,
+        height: height as usize,
+        baseline: baseline as usize,
+        max_length: max_length as usize,
+        old_layout: old_layout as i32,
+        comment_lines: comment_lines as usize,
+        print_direction: print_direction.map(|value| value as i32),
+        full_layout: full_layout.map(|value| value as i32),
+        codetag_count: codetag_count.map(|value| value as usize),
+    })
+}
+
+/// Splits `text` at whitespace, pairing every field with its 1-based column, `text`
+/// itself starting at column `first`.
+fn fields(text: &str, first: usize) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = None;
+    let mut fields = Vec::new();
+    for (column, (offset, c)) in (first..).zip(text.char_indices()) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((column, offset)),
+            (true, Some((field_column, field_offset))) => {
+                fields.push((field_column, &text[field_offset..offset]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((field_column, field_offset)) = start {
+        fields.push((field_column, &text[field_offset..]));
+    }
+    fields.into_iter()
+}
+
+/// Parses a code tag, which may be decimal, hexadecimal with a `0x` prefix or octal with a
+/// leading zero, optionally negative.
+pub(crate) fn parse_code(tag: &str) -> Option<i64> {
+    let (negative, digits) = match tag.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, tag),
+    };
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_code_tags() {
+        assert_eq!(parse_code("196"), Some(196));
+        assert_eq!(parse_code("0xC4"), Some(196));
+        assert_eq!(parse_code("0304"), Some(196));
+        assert_eq!(parse_code("-2"), Some(-2));
+        assert_eq!(parse_code("-0x10"), Some(-16));
+        assert_eq!(parse_code("0"), Some(0));
+        assert_eq!(parse_code("twelve"), None);
+        assert_eq!(parse_code("09"), None);
+    }
+
+    #[test]
+    fn splits_fields_with_columns() {
+        let fields: Vec<_> = fields(" 6 5  16", 7).collect();
+        assert_eq!(fields, [(8, "6"), (10, "5"), (13, "16")]);
+    }
+
+    #[test]
+    fn parses_headers() {
+        let header = parse_header("flf2a$ 6 5 16 15 13 0 24463 229").unwrap();
+        assert_eq!(header.hardblank, '$');
+        assert_eq!(header.height, 6);
+        assert_eq!(header.baseline, 5);
+        assert_eq!(header.old_layout, 15);
+        assert_eq!(header.comment_lines, 13);
+        assert_eq!(header.print_direction, Some(0));
+        assert_eq!(header.full_layout, Some(24463));
+        assert_eq!(header.codetag_count, Some(229));
+        let header = parse_header("flf2a# 1 1 2 -1 0").unwrap();
+        assert_eq!(header.hardblank, '#');
+        assert_eq!(header.print_direction, None);
+    }
+
+    #[test]
+    fn points_at_header_problems() {
+        let error = |line| {
+            let error = parse_header(line).unwrap_err();
+            (error.column, error.message)
+        };
+        assert_eq!(error("flf1a$ 1 1").0, 1);
+        assert_eq!(error("tlf2a$ 1 1").0, 11);
+        assert_eq!(error("flf2a 1 1 2 0 0").0, 6);
+        assert_eq!(
+            error("flf2a$ 6 x 16 15 13"),
+            (10, "invalid baseline 'x' in header".to_string())
+        );
+        assert_eq!(
+            error("flf2a$ 0 1 2 0 0"),
+            (8, "height must be between 1 and 1000, got 0".to_string())
+        );
+        assert_eq!(
+            error("flf2a$ 6 5 16 15"),
+            (17, "missing comment line count in header".to_string())
+        );
+        assert_eq!(error("flf2a$ 6 5 16 15 0 2").0, 20);
+    }
+}
@@ -0,0 +1,523 @@
+//! TOIlet-style filters that post-process a rendered [`Banner`] as a grid of cells.
+//!
+//! Filters are applied one after the other in the order they were given, each to the
+//! result of the previous one, after the banner has been coloured. Characters are mirrored
+//! or turned along with the grid where they have a counterpart, so that `/` becomes `\`
+//! when flipped. The placements of the characters of the message move with their cells.
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::banner::Banner;
+use crate::color::Rgb;
+use crate::render::CONTINUATION;
+
+/// A transformation of a rendered banner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Remove blank rows and columns around the banner.
+    Crop,
+    /// Mirror the banner horizontally.
+    Flip,
+    /// Mirror the banner vertically.
+    Flop,
+    /// Turn the banner upside down.
+    Rotate,
+    /// Turn the banner a quarter counterclockwise.
+    Left,
+    /// Turn the banner a quarter clockwise.
+    Right,
+    /// Draw a box around the banner.
+    Border,
+    /// Colour the banner in shades of blue and grey.
+    Metal,
+    /// Colour the banner in diagonal rainbow stripes.
+    Gay,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Filter, String> {
+        match s {
+            "crop" => Ok(Filter::Crop),
+            "flip" => Ok(Filter::Flip),
+            "flop" => Ok(Filter::Flop),
+            "rotate" | "180" => Ok(Filter::Rotate),
+            "left" => Ok(Filter::Left),
+            "right" => Ok(Filter::Right),
+            "border" => Ok(Filter::Border),
+            "metal" => Ok(Filter::Metal),
+            "gay" | "rainbow" => Ok(Filter::Gay),
+            _ => Err(format!(
+                "unknown filter '{}', expected crop, flip, flop, rotate, left, right, border, \
+                 metal or gay",
+                s
+            )),
+        }
+    }
+}
+
+impl Filter {
+    /// Whether the filter colours the banner.
+    pub fn colors(self) -> bool {
+        matches!(self, Filter::Metal | Filter::Gay)
+    }
+
+    /// Applies the filter to `banner`.
+    pub fn apply(self, banner: Banner) -> Banner {
+        let mut grid = Grid::from_banner(banner);
+        match self {
+            Filter::Crop => grid.crop(),
+            Filter::Flip => grid.flip(),
+            Filter::Flop => grid.flop(),
+            Filter::Rotate => {
+                grid.flip();
+                grid.flop();
+            }
+            Filter::Left => grid.turn(false),
+            Filter::Right => grid.turn(true),
+            Filter::Border => grid.border(),
+            Filter::Metal => {
+                grid.paint(|row, column| METAL[((row + column / 8) / 2) % METAL.len()])
+            }
+            Filter::Gay => grid.paint(|row, column| RAINBOW[(row + column) % RAINBOW.len()]),
+        }
+        grid.into_banner()
+    }
+}
+
+/// Colours of the metal filter, TOIlet's light blue, blue, light grey and dark grey.
+const METAL: [Rgb; 4] = [
+    Rgb::new(92, 92, 255),
+    Rgb::new(0, 0, 238),
+    Rgb::new(229, 229, 229),
+    Rgb::new(127, 127, 127),
+];
+
+/// Colours of the gay filter, TOIlet's light magenta, light red, yellow, light green,
+/// light cyan and light blue.
+const RAINBOW: [Rgb; 6] = [
+    Rgb::new(255, 0, 255),
+    Rgb::new(255, 0, 0),
+    Rgb::new(255, 255, 0),
+    Rgb::new(0, 255, 0),
+    Rgb::new(0, 255, 255),
+    Rgb::new(92, 92, 255),
+];
+
+/// Pairs of sub-characters that are mirror images left to right.
+const FLIPPED: &[(char, char)] = &[
+    ('/', '\\'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('`', '\''),
+    ('b', 'd'),
+    ('p', 'q'),
+    ('▌', '▐'),
+    ('▘', '▝'),
+    ('▖', '▗'),
+    ('┌', '┐'),
+    ('└', '┘'),
+    ('├', '┤'),
+    ('╭', '╮'),
+    ('╰', '╯'),
+    ('╱', '╲'),
+];
+
+/// Pairs of sub-characters that are mirror images top to bottom.
+const FLOPPED: &[(char, char)] = &[
+    ('/', '\\'),
+    ('_', '‾'),
+    ('^', 'v'),
+    ('\'', ','),
+    ('`', '.'),
+    ('b', 'p'),
+    ('d', 'q'),
+    ('M', 'W'),
+    ('m', 'w'),
+    ('n', 'u'),
+    ('▀', '▄'),
+    ('▘', '▖'),
+    ('▝', '▗'),
+    ('┌', '└'),
+    ('┐', '┘'),
+    ('┬', '┴'),
+    ('╭', '╰'),
+    ('╮', '╯'),
+    ('╱', '╲'),
+];
+
+/// Cycles of sub-characters that turn into each other a quarter clockwise.
+const TURNED: &[&[char]] = &[
+    &['|', '-'],
+    &['/', '\\'],
+    &['│', '─'],
+    &['┌', '┐', '┘', '└'],
+    &['├', '┬', '┤', '┴'],
+    &['╭', '╮', '╯', '╰'],
+    &['▀', '▐', '▄', '▌'],
+    &['╱', '╲'],
+];
+
+fn mirror(c: char, pairs: &[(char, char)]) -> char {
+    pairs
+        .iter()
+        .find_map(|&(a, b)| match c {
+            _ if c == a => Some(b),
+            _ if c == b => Some(a),
+            _ => None,
+        })
+        .unwrap_or(c)
+}
+
+/// `c` turned a quarter clockwise, or counterclockwise.
+fn turn(c: char, clockwise: bool) -> char {
+    for cycle in TURNED {
+        if let Some(index) = cycle.iter().position(|&member| member == c) {
+            let next = if clockwise {
+                index + 1
+            } else {
+                index + cycle.len() - 1
+            };
+            return cycle[next % cycle.len()];
+        }
+    }
+    c
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    c: char,
+    color: Option<Rgb>,
+}
+
+const BLANK: Cell = Cell {
+    c: ' ',
+    color: None,
+};
+
+/// A banner as a rectangle of cells one column wide.
+struct Grid {
+    cells: Vec<Vec<Cell>>,
+    width: usize,
+    banner: Banner,
+    colored: bool,
+}
+
+impl Grid {
+    fn from_banner(banner: Banner) -> Grid {
+        let width = banner.width();
+        let cells = (0..banner.height())
+            .map(|row| {
+                let mut cells = vec![BLANK; width];
+                // Zero-width sub-characters at the end of a row start past its last column.
+                for (column, c) in banner.cells(row).filter(|&(column, _)| column < width) {
+                    let color = banner.color(row, column);
+                    cells[column] = Cell { c, color };
+                    if column + 1 < width && is_double_width(c) {
+                        cells[column + 1] = Cell {
+                            c: CONTINUATION,
+                            color,
+                        };
+                    }
+                }
+                cells
+            })
+            .collect();
+        Grid {
+            cells,
+            width,
+            colored: !banner.colors.is_empty(),
+            banner,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn into_banner(self) -> Banner {
+        let rows = self
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.c)
+                    .filter(|&c| c != CONTINUATION)
+                    .collect()
+            })
+            .collect();
+        let colors = if self.colored {
+            self.cells
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.color).collect())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Banner {
+            rows,
+            colors,
+            ..self.banner
+        }
+    }
+
+    /// Moves every placement with `transform`, which maps row and column ranges.
+    fn place(
+        &mut self,
+        transform: impl Fn(Range<usize>, Range<usize>) -> (Range<usize>, Range<usize>),
+    ) {
+        for placement in &mut self.banner.placements {
+            let (rows, columns) = transform(placement.rows.clone(), placement.columns.clone());
+            placement.rows = rows;
+            placement.columns = columns;
+        }
+    }
+
+    fn crop(&mut self) {
+        let filled = |cell: &Cell| cell.c != ' ';
+        let rows: Vec<usize> = (0..self.height())
+            .filter(|&row| self.cells[row].iter().any(filled))
+            .collect();
+        let (top, bottom) = match (rows.first(), rows.last()) {
+            (Some(&top), Some(&bottom)) => (top, bottom + 1),
+            _ => (0, 0),
+        };
+        let columns = |row: &Vec<Cell>| {
+            let start = row.iter().position(filled);
+            start.zip(row.iter().rposition(filled))
+        };
+        let (left, right) = self.cells[top..bottom]
+            .iter()
+            .filter_map(columns)
+            .fold((usize::MAX, 0), |(left, right), (start, end)| {
+                (left.min(start), right.max(end + 1))
+            });
+        let left = left.min(right);
+        self.cells = self.cells[top..bottom]
+            .iter()
+            .map(|row| row[left..right].to_vec())
+            .collect();
+        self.width = right - left;
+        let clamp = |range: Range<usize>, start: usize, end: usize| {
+            range.start.clamp(start, end) - start..range.end.clamp(start, end) - start
+        };
+        self.place(|rows, columns| (clamp(rows, top, bottom), clamp(columns, left, right)));
+    }
+
+    fn flip(&mut self) {
+        for row in &mut self.cells {
+            row.reverse();
+            for cell in row.iter_mut() {
+                cell.c = mirror(cell.c, FLIPPED);
+            }
+            // Reversing puts the second column of double-width sub-characters first.
+            for column in 1..row.len() {
+                if row[column - 1].c == CONTINUATION && is_double_width(row[column].c) {
+                    row.swap(column - 1, column);
+                }
+            }
+        }
+        let width = self.width;
+        self.place(|rows, columns| (rows, mirrored(columns, width)));
+    }
+
+    fn flop(&mut self) {
+        self.cells.reverse();
+        for cell in self.cells.iter_mut().flatten() {
+            cell.c = mirror(cell.c, FLOPPED);
+        }
+        let height = self.height();
+        self.place(|rows, columns| (mirrored(rows, height), columns));
+    }
+
+    /// Turns the grid a quarter clockwise or counterclockwise. Double-width sub-characters
+    /// would end up on top of their second column, they are blanked instead.
+    fn turn(&mut self, clockwise: bool) {
+        let (width, height) = (self.width, self.height());
+        self.cells = (0..width)
+            .map(|row| {
+                (0..height)
+                    .map(|column| {
+                        let cell = if clockwise {
+                            self.cells[height - 1 - column][row]
+                        } else {
+                            self.cells[column][width - 1 - row]
+                        };
+                        match cell.c {
+                            CONTINUATION => BLANK,
+                            c if is_double_width(c) => BLANK,
+                            c => Cell {
+                                c: turn(c, clockwise),
+                                ..cell
+                            },
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        self.width = height;
+        self.place(|rows, columns| {
+            if clockwise {
+                (columns, mirrored(rows, height))
+            } else {
+                (mirrored(columns, width), rows)
+            }
+        });
+    }
+
+    fn border(&mut self) {
+        let edge = |c| Cell { c, color: None };
+        let mut cells = Vec::with_capacity(self.height() + 2);
+        let line = |left, right| {
+            let mut row = vec![edge('─'); self.width + 2];
+            row[0] = edge(left);
+            row[self.width + 1] = edge(right);
+            row
+        };
+        cells.push(line('┌', '┐'));
+        for row in &self.cells {
+            let mut bordered = Vec::with_capacity(self.width + 2);
+            bordered.push(edge('│'));
+            bordered.extend(row);
+            bordered.push(edge('│'));
+            cells.push(bordered);
+        }
+        cells.push(line('└', '┘'));
+        self.cells = cells;
+        self.width += 2;
+        self.place(|rows, columns| {
+            (
+                rows.start + 1..rows.end + 1,
+                columns.start + 1..columns.end + 1,
+            )
+        });
+    }
+
+    /// Colours every cell that is not blank with `color`, given its row and column.
+    fn paint(&mut self, color: impl Fn(usize, usize) -> Rgb) {
+        for (row, cells) in self.cells.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                if cell.c != ' ' {
+                    cell.color = Some(color(row, column));
+                }
+            }
+        }
+        self.colored = true;
+    }
+}
+
+/// `range` of a line `length` long, counted from the other end.
+fn mirrored(range: Range<usize>, length: usize) -> Range<usize> {
+    length.saturating_sub(range.end)..length.saturating_sub(range.start)
+}
+
+/// Whether `c` is the first half of a double-width sub-character. Unlike when rendering,
+/// the [`CONTINUATION`] half does not count, as flipping has to tell the two apart.
+fn is_double_width(c: char) -> bool {
+    c.width() == Some(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::{OutputFormat, Placement};
+
+    fn banner(rows: &[&str]) -> Banner {
+        Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: vec![Placement {
+                index: 0,
+                code: 'x' as i64,
+                rows: 1..2,
+                columns: 1..3,
+            }],
+            colors: Vec::new(),
+            format: OutputFormat::Text,
+        }
+    }
+
+    fn apply(filters: &[Filter], rows: &[&str]) -> Banner {
+        filters
+            .iter()
+            .fold(banner(rows), |banner, filter| filter.apply(banner))
+    }
+
+    #[test]
+    fn parses_filter_names() {
+        assert_eq!("crop".parse(), Ok(Filter::Crop));
+        assert_eq!("180".parse(), Ok(Filter::Rotate));
+        assert_eq!("rainbow".parse(), Ok(Filter::Gay));
+        assert!("blur".parse::<Filter>().is_err());
+        assert!(Filter::Metal.colors());
+        assert!(!Filter::Border.colors());
+    }
+
+    #[test]
+    fn crops_blank_margins() {
+        let cropped = apply(&[Filter::Crop], &["     ", "  /\\ ", "", "     "]);
+        assert_eq!(cropped.rows, ["/\\"]);
+        assert_eq!(cropped.placements[0].rows, 0..1);
+        assert_eq!(cropped.placements[0].columns, 0..1);
+        assert_eq!(
+            apply(&[Filter::Crop], &["   ", " "]).rows,
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn mirrors_characters() {
+        let flipped = apply(&[Filter::Flip], &["/_(", "b全 "]);
+        assert_eq!(flipped.rows, [" )_\\", " 全d"]);
+        assert_eq!(flipped.placements[0].columns, 1..3);
+        let flopped = apply(&[Filter::Flop], &["/_(", "b  "]);
+        assert_eq!(flopped.rows, ["p  ", "\\‾("]);
+        assert_eq!(flopped.placements[0].rows, 0..1);
+        assert_eq!(apply(&[Filter::Rotate], &["/_", "b "]).rows, [" q", "‾/"]);
+    }
+
+    #[test]
+    fn drops_sub_characters_past_the_last_column() {
+        let flipped = apply(&[Filter::Flip], &["a\u{301}"]);
+        assert_eq!(flipped.rows, ["a"]);
+    }
+
+    #[test]
+    fn turns_a_quarter() {
+        let left = apply(&[Filter::Left], &["ab-", "c/ "]);
+        assert_eq!(left.rows, ["| ", "b\\", "ac"]);
+        assert_eq!(left.placements[0].rows, 0..2);
+        assert_eq!(left.placements[0].columns, 1..2);
+        let right = apply(&[Filter::Right], &["ab-", "c/ "]);
+        assert_eq!(right.rows, ["ca", "\\b", " |"]);
+        assert_eq!(right.placements[0].rows, 1..3);
+        assert_eq!(right.placements[0].columns, 0..1);
+        assert_eq!(
+            apply(&[Filter::Left, Filter::Right], &["a全"]).rows,
+            ["a  "]
+        );
+    }
+
+    #[test]
+    fn draws_borders() {
+        let bordered = apply(&[Filter::Border], &["ab", "c"]);
+        assert_eq!(bordered.rows, ["┌──┐", "│ab│", "│c │", "└──┘"]);
+        assert_eq!(bordered.placements[0].rows, 2..3);
+        assert_eq!(bordered.placements[0].columns, 2..4);
+    }
+
+    #[test]
+    fn colours_filled_cells() {
+        let painted = apply(&[Filter::Gay], &["a b"]);
+        assert_eq!(painted.colors, [[Some(RAINBOW[0]), None, Some(RAINBOW[2])]]);
+        let painted = apply(&[Filter::Metal, Filter::Flip], &["ab"]);
+        assert_eq!(painted.colors, [[Some(METAL[0]); 2]]);
+        assert!(apply(&[Filter::Flip], &["ab"]).colors.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::bundled;
+use crate::error::FigctlError;
+use crate::figfont::FigFont;
+use crate::search::{self, SearchPath};
+
+/// Name of the font that is always compiled into the binary.
+const STANDARD: &str = "standard";
+
+const STANDARD_SOURCE: &str = include_str!("../fonts/standard.flf");
+
+/// A loaded font together with where it came from.
+pub struct Font {
+    pub name: String,
+    /// File the font was read from, `None` for fonts compiled into the binary.
+    pub path: Option<PathBuf>,
+    pub figfont: FigFont,
+}
+
+impl Font {
+    /// Describes where the font was loaded from, for diagnostics.
+    pub fn origin(&self) -> String {
+        match &self.path {
+            Some(path) => path.display().to_string(),
+            None => format!("built-in {}", self.name),
+        }
+    }
+}
+
+/// Loads the font described by `spec`, or the standard font when no font was requested.
+///
+/// `spec` is treated as a path when it points to an existing file or contains a path
+/// separator, otherwise it is looked up as `<spec>.flf` or `<spec>.tlf`, possibly gzipped,
+/// on the `search_path` and then among the fonts compiled into the binary. Font files may
+/// be gzipped or zipped whatever their name.
+pub fn load(spec: Option<&str>, search_path: &SearchPath) -> Result<Font, FigctlError> {
+    let spec = match spec {
+        Some(spec) => spec,
+        None => return standard(),
+    };
+    let path = Path::new(spec);
+    if path.is_file() || spec.contains(std::path::is_separator) {
+        return load_file(path);
+    }
+    match search_path.find(spec) {
+        Some(path) => load_file(&path),
+        None if spec == STANDARD => standard(),
+        None if bundled::find(spec).is_some() => load_bundled(spec),
+        None => Err(FigctlError::FontNotFound {
+            name: spec.to_string(),
+            searched: search_path.dirs().to_vec(),
+        }),
+    }
+}
+
+/// Names of the fonts compiled into the binary.
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    iter::once(STANDARD).chain(bundled::FONTS.iter().map(|(name, _)| *name))
+}
+
+fn standard() -> Result<Font, FigctlError> {
+    let figfont = parse(STANDARD_SOURCE, &format!("built-in {}", STANDARD))?;
+    Ok(Font {
+        name: STANDARD.to_string(),
+        path: None,
+        figfont,
+    })
+}
+
+fn load_bundled(name: &str) -> Result<Font, FigctlError> {
+    let source = bundled::find(name).unwrap_or_default();
+    let figfont = parse(source, &format!("built-in {}", name))?;
+    Ok(Font {
+        name: name.to_string(),
+        path: None,
+        figfont,
+    })
+}
+
+/// Loads the font file at `path`, which may be gzipped or zipped whatever its name.
+pub fn load_file(path: &Path) -> Result<Font, FigctlError> {
+    let origin = path.display().to_string();
+    let content = fs::read(path).map_err(|source| FigctlError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    // A damaged archive makes a malformed font, not a file that cannot be read.
+    let content = archive::unpack(content).map_err(|err| FigctlError::FontParse {
+        font: origin.clone(),
+        line: 1,
+        column: 1,
+        message: format!("corrupt archive: {}", err),
+    })?;
+    let figfont = parse(&decode(content), &origin)?;
+    Ok(Font {
+        name: font_name(path),
+        path: Some(path.to_path_buf()),
+        figfont,
+    })
+}
+
+/// Parses a font, pointing errors at the font they were found in.
+fn parse(content: &str, origin: &str) -> Result<FigFont, FigctlError> {
+    FigFont::parse(content).map_err(|err| FigctlError::FontParse {
+        font: origin.to_string(),
+        line: err.line,
+        column: err.column,
+        message: err.message,
+    })
+}
+
+/// Decodes a font or control file. FIGfonts predate UTF-8 and many carry Latin-1 in their
+/// comments, so anything that is not valid UTF-8 is read as Latin-1 instead.
+pub(crate) fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect())
+}
+
+fn font_name(path: &Path) -> String {
+    search::font_name(path)
+        .or_else(|| path.file_stem()?.to_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_hardblanks() {
+        let font = standard().unwrap();
+        assert_eq!(font.figfont.header.hardblank, '$');
+        assert_eq!(font.figfont.character(' ').unwrap()[0], " $");
+    }
+
+    #[test]
+    fn keeps_the_code_tagged_characters() {
+        let font = standard().unwrap();
+        assert_eq!(font.figfont.header.codetag_count, Some(229));
+        assert_eq!(font.figfont.character('é').unwrap()[1], "   /_/ ");
+        assert!(font.figfont.character('\u{141}').is_some());
+        assert!(font.figfont.character('\u{2DD}').is_some());
+    }
+
+    #[test]
+    fn reads_latin1_as_a_fallback() {
+        assert_eq!(decode(b"caf\xe9".to_vec()), "café");
+        assert_eq!(decode("café".as_bytes().to_vec()), "café");
+    }
+
+    #[test]
+    fn reports_the_position_of_parse_errors() {
+        match parse("flf2a$ 6 5 16 15 x", "broken") {
+            Err(FigctlError::FontParse {
+                font, line, column, ..
+            }) => assert_eq!((font.as_str(), line, column), ("broken", 1, 18)),
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_unknown_fonts() {
+        let search_path = SearchPath::new(&[]);
+        assert!(matches!(
+            load(Some("no-such-font"), &search_path),
+            Err(FigctlError::FontNotFound { .. })
+        ));
+        assert_eq!(
+            load(Some("standard"), &search_path).unwrap().name,
+            "standard"
+        );
+    }
+}
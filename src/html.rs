@@ -0,0 +1,171 @@
+//! HTML output: a `<pre>` block that can be pasted into a page, or a standalone document.
+//!
+//! Rows are written as plain text with every special character escaped, so copying the
+//! banner from the page gives back exactly the text output. Coloured runs of sub-characters
+//! are wrapped in `<span>` elements with an inline CSS colour, blanks never start a span.
+
+use std::io::{self, Write};
+
+use crate::banner::Banner;
+use crate::color::Rgb;
+
+/// Options of the HTML output format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Write a complete HTML document rather than a `<pre>` block to embed.
+    pub standalone: bool,
+    /// CSS font family of the block, the browser's monospaced font when `None`.
+    pub font_family: Option<String>,
+    pub background: Option<Rgb>,
+}
+
+/// Font family of standalone documents when none was given.
+const DEFAULT_FONT_FAMILY: &str = "monospace";
+
+pub(crate) fn write(
+    banner: &Banner,
+    options: &HtmlOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    if options.standalone {
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html>")?;
+        writeln!(out, "<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>figctl</title>")?;
+        writeln!(out, "</head>")?;
+        match options.background {
+            Some(background) => writeln!(out, "<body style=\"background: {}\">", background)?,
+            None => writeln!(out, "<body>")?,
+        }
+    }
+    let font_family = match (&options.font_family, options.standalone) {
+        (Some(font_family), _) => Some(font_family.as_str()),
+        (None, true) => Some(DEFAULT_FONT_FAMILY),
+        (None, false) => None,
+    };
+    let mut style = Vec::new();
+    if let Some(font_family) = font_family {
+        style.push(format!("font-family: {}", escape(font_family)));
+    }
+    if let Some(background) = options.background {
+        style.push(format!("background: {}", background));
+    }
+    if style.is_empty() {
+        write!(out, "<pre class=\"figctl\">")?;
+    } else {
+        write!(out, "<pre class=\"figctl\" style=\"{}\">", style.join("; "))?;
+    }
+    for row in 0..banner.height() {
+        if row > 0 {
+            writeln!(out)?;
+        }
+        write_row(banner, row, out)?;
+    }
+    writeln!(out, "</pre>")?;
+    if options.standalone {
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")?;
+    }
+    Ok(())
+}
+
+/// Writes `row`, opening a span wherever the colour of a visible sub-character changes.
+fn write_row(banner: &Banner, row: usize, out: &mut impl Write) -> io::Result<()> {
+    let mut current = None;
+    for (column, c) in banner.cells(row) {
+        if c != ' ' {
+            let color = banner.color(row, column);
+            if color != current {
+                if current.is_some() {
+                    write!(out, "</span>")?;
+                }
+                if let Some(color) = color {
+                    write!(out, "<span style=\"color: {}\">", color)?;
+                }
+                current = color;
+            }
+        }
+        write!(out, "{}", escape(c.encode_utf8(&mut [0; 4])))?;
+    }
+    if current.is_some() {
+        write!(out, "</span>")?;
+    }
+    Ok(())
+}
+
+/// Escapes the characters that are special in HTML and XML text and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::OutputFormat;
+
+    fn banner(rows: &[&str]) -> Banner {
+        Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: Vec::new(),
+            colors: Vec::new(),
+            format: OutputFormat::Html(HtmlOptions::default()),
+        }
+    }
+
+    fn html(banner: &Banner) -> String {
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(
+            html(&banner(&["<a href=\"x\">", "'&' "])),
+            "<pre class=\"figctl\">&lt;a href=&quot;x&quot;&gt;\n&#39;&amp;&#39; </pre>\n"
+        );
+    }
+
+    #[test]
+    fn wraps_coloured_runs_in_spans() {
+        let red = Some(Rgb::new(255, 0, 0));
+        let blue = Some(Rgb::new(0, 0, 255));
+        let mut banner = banner(&["ab c", "d"]);
+        banner.colors = vec![vec![red, red, None, blue], vec![None]];
+        assert_eq!(
+            html(&banner),
+            "<pre class=\"figctl\"><span style=\"color: #ff0000\">ab </span>\
+             <span style=\"color: #0000ff\">c</span>\nd</pre>\n"
+        );
+    }
+
+    #[test]
+    fn writes_standalone_documents() {
+        let mut banner = banner(&["|_|"]);
+        banner.format = OutputFormat::Html(HtmlOptions {
+            standalone: true,
+            font_family: None,
+            background: Some(Rgb::new(0, 0, 0)),
+        });
+        let document = html(&banner);
+        assert!(document.starts_with("<!DOCTYPE html>\n<html>\n"));
+        assert!(document.contains("<body style=\"background: #000000\">\n"));
+        assert!(document.contains(
+            "<pre class=\"figctl\" style=\"font-family: monospace; background: #000000\">|_|</pre>"
+        ));
+        assert!(document.ends_with("</body>\n</html>\n"));
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::error::FigctlError;
+
+/// Where the message to render comes from.
+#[derive(Debug)]
+pub enum Source {
+    /// The message given on the command line.
+    Text(String),
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Reads the message as raw bytes, which are decoded in the input mode of the control
+    /// files.
+    pub fn read(self) -> Result<Vec<u8>, FigctlError> {
+        match self {
+            Source::Text(text) => Ok(text.into_bytes()),
+            Source::Stdin => {
+                let mut text = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut text)
+                    .map_err(|source| FigctlError::Io {
+                        path: PathBuf::from("standard input"),
+                        source,
+                    })?;
+                Ok(text)
+            }
+            Source::File(path) => {
+                fs::read(&path).map_err(|source| FigctlError::Io { path, source })
+            }
+        }
+    }
+}
+
+/// Splits the input into the messages that are rendered as separate banners.
+///
+/// By default every line becomes its own banner. In paragraph mode consecutive lines are
+/// joined with a space and only blank lines start a new banner, so input without blank
+/// lines ends up as a single banner.
+///
+/// The text is split at newline bytes, which every supported input encoding leaves alone.
+pub fn banners(text: &[u8], paragraph: bool) -> Vec<Vec<u8>> {
+    if !paragraph {
+        return lines(text).into_iter().map(<[u8]>::to_vec).collect();
+    }
+    let mut banners = Vec::new();
+    let mut current: Vec<&[u8]> = Vec::new();
+    for line in lines(text).into_iter().map(<[u8]>::trim_ascii) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                banners.push(current.join(&b' '));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        banners.push(current.join(&b' '));
+    }
+    banners
+}
+
+/// Splits `text` into lines the way [`str::lines`] does.
+fn lines(text: &[u8]) -> Vec<&[u8]> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let text = text.strip_suffix(b"\n").unwrap_or(text);
+    text.split(|&byte| byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_banner_per_line() {
+        assert_eq!(
+            banners(b"v1.2\r\nbuild 7\n", false),
+            [&b"v1.2"[..], b"build 7"]
+        );
+        assert_eq!(banners(b"a\n\nb", false), [&b"a"[..], b"", b"b"]);
+        assert!(banners(b"", false).is_empty());
+    }
+
+    #[test]
+    fn paragraphs_join_lines() {
+        assert_eq!(
+            banners(b"one\n two \n\n\nthree\n", true),
+            [&b"one two"[..], b"three"]
+        );
+        assert!(banners(b"\n\n", true).is_empty());
+    }
+}
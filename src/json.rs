@@ -0,0 +1,180 @@
+//! JSON output: the banner together with how it was made and where every character went.
+//!
+//! The document holds the message, the font and the layout it was rendered with, the
+//! rendered lines and, for every character of the message that was drawn, the rows and
+//! terminal columns of its FIGcharacter. Ranges are written as `[start, end]` with the end
+//! excluded, and columns overlap where FIGcharacters were smushed together.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::banner::Banner;
+use crate::layout::{Layout, Mode};
+
+/// Options of the JSON output format, which describe where the banner came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Name of the font.
+    pub font: String,
+    /// File the font was read from, `None` for fonts compiled into the binary.
+    pub font_path: Option<PathBuf>,
+    /// Height of the font's FIGcharacters.
+    pub font_height: usize,
+    /// Layout the font was rendered with, after overrides.
+    pub layout: Layout,
+}
+
+pub(crate) fn write(
+    banner: &Banner,
+    options: &JsonOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(out, "  \"text\": {},", string(&banner.text))?;
+    writeln!(out, "  \"font\": {{")?;
+    writeln!(out, "    \"name\": {},", string(&options.font))?;
+    match &options.font_path {
+        Some(path) => writeln!(out, "    \"path\": {},", string(&path.to_string_lossy()))?,
+        None => writeln!(out, "    \"path\": null,")?,
+    }
+    writeln!(out, "    \"height\": {}", options.font_height)?;
+    writeln!(out, "  }},")?;
+    let layout = &options.layout;
+    writeln!(out, "  \"layout\": {{")?;
+    writeln!(
+        out,
+        "    \"horizontal\": {},",
+        mode(
+            layout.horizontal,
+            "full width",
+            layout.horizontal_rule_names()
+        )
+    )?;
+    writeln!(
+        out,
+        "    \"vertical\": {}",
+        mode(layout.vertical, "full height", layout.vertical_rule_names())
+    )?;
+    writeln!(out, "  }},")?;
+    writeln!(out, "  \"width\": {},", banner.width())?;
+    writeln!(out, "  \"height\": {},", banner.height())?;
+    let lines: Vec<String> = banner.rows.iter().map(|row| string(row)).collect();
+    writeln!(out, "  \"lines\": {},", array(&lines))?;
+    let characters: Vec<String> = banner
+        .placements
+        .iter()
+        .map(|placement| {
+            let character = u32::try_from(placement.code)
+                .ok()
+                .and_then(char::from_u32)
+                .map_or("null".to_string(), |c| string(c.encode_utf8(&mut [0; 4])));
+            format!(
+                "{{\"index\": {}, \"character\": {}, \"code\": {}, \"rows\": [{}, {}], \
+                 \"columns\": [{}, {}]}}",
+                placement.index,
+                character,
+                placement.code,
+                placement.rows.start,
+                placement.rows.end,
+                placement.columns.start,
+                placement.columns.end
+            )
+        })
+        .collect();
+    writeln!(out, "  \"characters\": {}", array(&characters))?;
+    writeln!(out, "}}")
+}
+
+/// A horizontal or vertical layout mode as an object, `full` naming full width or height.
+fn mode(mode: Mode, full: &str, rules: Vec<&str>) -> String {
+    let name = match mode {
+        Mode::FullWidth => full,
+        Mode::Fitting => "fitting",
+        Mode::Smushing => "smushing",
+    };
+    let rules = match mode {
+        Mode::Smushing => rules.into_iter().map(string).collect(),
+        _ => Vec::new(),
+    };
+    format!(
+        "{{\"mode\": {}, \"rules\": [{}]}}",
+        string(name),
+        rules.join(", ")
+    )
+}
+
+/// An array of already encoded values, one per line.
+fn array(values: &[String]) -> String {
+    if values.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n    {}\n  ]", values.join(",\n    "))
+}
+
+/// `text` as a JSON string literal.
+fn string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(literal, "\\u{:04x}", c as u32);
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::{OutputFormat, Placement};
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(string("a\"b\\c\nd\u{1}é"), "\"a\\\"b\\\\c\\nd\\u0001é\"");
+    }
+
+    #[test]
+    fn writes_the_banner_with_its_origin() {
+        let banner = Banner {
+            rows: vec!["/\\".to_string(), "\"".to_string()],
+            text: " x".to_string(),
+            placements: vec![Placement {
+                index: 1,
+                code: 'x' as i64,
+                rows: 0..2,
+                columns: 0..2,
+            }],
+            colors: Vec::new(),
+            format: OutputFormat::Json(JsonOptions {
+                font: "tiny".to_string(),
+                font_path: Some(PathBuf::from("fonts/tiny.flf")),
+                font_height: 2,
+                layout: Layout::from_header(15, None),
+            }),
+        };
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\n  \"text\": \" x\",\n  \"font\": {\n    \"name\": \"tiny\",\n    \
+             \"path\": \"fonts/tiny.flf\",\n    \"height\": 2\n  },\n  \"layout\": {\n    \
+             \"horizontal\": {\"mode\": \"smushing\", \"rules\": [\"equal\", \"underscore\", \
+             \"hierarchy\", \"pair\"]},\n    \
+             \"vertical\": {\"mode\": \"full height\", \"rules\": []}\n  },\n  \
+             \"width\": 2,\n  \"height\": 2,\n  \
+             \"lines\": [\n    \"/\\\\\",\n    \"\\\"\"\n  ],\n  \
+             \"characters\": [\n    {\"index\": 1, \"character\": \"x\", \"code\": 120, \
+             \"rows\": [0, 2], \"columns\": [0, 2]}\n  ]\n}\n"
+        );
+    }
+}
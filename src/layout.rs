@@ -0,0 +1,254 @@
+use std::fmt;
+
+/// Horizontal smushing rule bits of the FIGfont `full_layout` header field.
+const HORIZONTAL_RULES: [(i32, &str); 6] = [
+    (EQUAL, "equal"),
+    (UNDERSCORE, "underscore"),
+    (HIERARCHY, "hierarchy"),
+    (PAIR, "pair"),
+    (BIG_X, "big x"),
+    (HARDBLANK, "hardblank"),
+];
+
+/// Vertical smushing rule bits of the FIGfont `full_layout` header field.
+const VERTICAL_RULES: [(i32, &str); 5] = [
+    (VERTICAL_EQUAL, "equal"),
+    (VERTICAL_UNDERSCORE, "underscore"),
+    (VERTICAL_HIERARCHY, "hierarchy"),
+    (HORIZONTAL_LINE, "horizontal line"),
+    (VERTICAL_LINE, "vertical line"),
+];
+
+pub const EQUAL: i32 = 1;
+pub const UNDERSCORE: i32 = 2;
+pub const HIERARCHY: i32 = 4;
+pub const PAIR: i32 = 8;
+pub const BIG_X: i32 = 16;
+pub const HARDBLANK: i32 = 32;
+
+pub const VERTICAL_EQUAL: i32 = 256;
+pub const VERTICAL_UNDERSCORE: i32 = 512;
+pub const VERTICAL_HIERARCHY: i32 = 1024;
+pub const HORIZONTAL_LINE: i32 = 2048;
+pub const VERTICAL_LINE: i32 = 4096;
+
+const HORIZONTAL_FITTING: i32 = 64;
+const HORIZONTAL_SMUSHING: i32 = 128;
+const VERTICAL_FITTING: i32 = 8192;
+const VERTICAL_SMUSHING: i32 = 16384;
+
+/// How adjacent FIGcharacters (or FIG lines) are joined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    FullWidth,
+    Fitting,
+    Smushing,
+}
+
+/// Layout declared by a FIGfont header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub horizontal: Mode,
+    /// Horizontal smushing rule bits, the low six bits of `full_layout`.
+    pub horizontal_rules: i32,
+    pub vertical: Mode,
+    /// Vertical smushing rule bits, bits 8 to 12 of `full_layout`.
+    pub vertical_rules: i32,
+}
+
+impl Layout {
+    /// Decodes the header's layout fields, deriving the layout from `old_layout`
+    /// for fonts that predate `full_layout`.
+    pub fn from_header(old_layout: i32, full_layout: Option<i32>) -> Layout {
+        let full_layout = full_layout.unwrap_or(match old_layout {
+            -1 => 0,
+            0 => HORIZONTAL_FITTING,
+            rules => HORIZONTAL_SMUSHING | (rules & 63),
+        });
+        let horizontal = if full_layout & HORIZONTAL_SMUSHING != 0 {
+            Mode::Smushing
+        } else if full_layout & HORIZONTAL_FITTING != 0 {
+            Mode::Fitting
+        } else {
+            Mode::FullWidth
+        };
+        let vertical = if full_layout & VERTICAL_SMUSHING != 0 {
+            Mode::Smushing
+        } else if full_layout & VERTICAL_FITTING != 0 {
+            Mode::Fitting
+        } else {
+            Mode::FullWidth
+        };
+        Layout {
+            horizontal,
+            horizontal_rules: full_layout & 63,
+            vertical,
+            vertical_rules: full_layout & (31 << 8),
+        }
+    }
+}
+
+/// Layout requested on the command line in place of the font's own, for either
+/// direction. The horizontal one follows figlet's `-W`, `-k`, `-S` and `-m` options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutOverride {
+    /// Full width, or full height vertically.
+    Full,
+    Fitting,
+    /// Smushing with the given rules, numbered from 1 in the order of the FIGfont spec,
+    /// universal smushing when there are none.
+    Smushing(i32),
+    /// Smushing with the font's own rules, even when the font asks for fitting or full size.
+    ForceSmushing,
+}
+
+impl LayoutOverride {
+    /// Interprets a figlet `-m` style layout mode: -1 is full size, 0 fitting, a positive
+    /// value smushing with its low bits as rules, and anything below -1 keeps the font's
+    /// layout.
+    pub fn from_mode(mode: i32) -> Option<LayoutOverride> {
+        match mode {
+            mode if mode < -1 => None,
+            -1 => Some(LayoutOverride::Full),
+            0 => Some(LayoutOverride::Fitting),
+            rules => Some(LayoutOverride::Smushing(rules)),
+        }
+    }
+
+    /// Mode and rule bits replacing the font's `rules`, `to_bits` placing requested rules
+    /// where the header keeps them for this direction.
+    fn apply(self, rules: i32, to_bits: impl Fn(i32) -> i32) -> (Mode, i32) {
+        match self {
+            LayoutOverride::Full => (Mode::FullWidth, rules),
+            LayoutOverride::Fitting => (Mode::Fitting, rules),
+            LayoutOverride::Smushing(requested) => (Mode::Smushing, to_bits(requested)),
+            LayoutOverride::ForceSmushing => (Mode::Smushing, rules),
+        }
+    }
+}
+
+impl Layout {
+    /// Names of the horizontal smushing rules, which only apply when smushing.
+    pub fn horizontal_rule_names(&self) -> Vec<&'static str> {
+        rule_names(self.horizontal_rules, &HORIZONTAL_RULES)
+    }
+
+    /// Names of the vertical smushing rules, which only apply when smushing.
+    pub fn vertical_rule_names(&self) -> Vec<&'static str> {
+        rule_names(self.vertical_rules, &VERTICAL_RULES)
+    }
+
+    /// Replaces the horizontal part of the layout.
+    pub fn with_horizontal(self, horizontal: LayoutOverride) -> Layout {
+        let (mode, rules) = horizontal.apply(self.horizontal_rules, |rules| rules & 63);
+        Layout {
+            horizontal: mode,
+            horizontal_rules: rules,
+            ..self
+        }
+    }
+
+    /// Replaces the vertical part of the layout.
+    pub fn with_vertical(self, vertical: LayoutOverride) -> Layout {
+        let (mode, rules) = vertical.apply(self.vertical_rules, |rules| (rules & 31) << 8);
+        Layout {
+            vertical: mode,
+            vertical_rules: rules,
+            ..self
+        }
+    }
+}
+
+fn rule_names(rule_bits: i32, rules: &[(i32, &'static str)]) -> Vec<&'static str> {
+    rules
+        .iter()
+        .filter(|(bit, _)| rule_bits & bit != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn write_mode(f: &mut fmt::Formatter, mode: Mode, full: &str, names: Vec<&str>) -> fmt::Result {
+    match mode {
+        Mode::FullWidth => write!(f, "{}", full),
+        Mode::Fitting => write!(f, "fitting"),
+        Mode::Smushing => {
+            if names.is_empty() {
+                write!(f, "universal smushing")
+            } else {
+                write!(f, "smushing ({})", names.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "horizontal ")?;
+        write_mode(
+            f,
+            self.horizontal,
+            "full width",
+            self.horizontal_rule_names(),
+        )?;
+        write!(f, ", vertical ")?;
+        write_mode(f, self.vertical, "full height", self.vertical_rule_names())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_the_layout_from_old_layout() {
+        let layout = Layout::from_header(-1, None);
+        assert_eq!(layout.horizontal, Mode::FullWidth);
+        let layout = Layout::from_header(0, None);
+        assert_eq!(layout.horizontal, Mode::Fitting);
+        let layout = Layout::from_header(15, None);
+        assert_eq!(layout.horizontal, Mode::Smushing);
+        assert_eq!(layout.horizontal_rules, 15);
+        assert_eq!(layout.vertical, Mode::FullWidth);
+    }
+
+    #[test]
+    fn prefers_full_layout() {
+        let layout = Layout::from_header(15, Some(24463));
+        assert_eq!(layout.horizontal, Mode::Smushing);
+        assert_eq!(layout.horizontal_rules, 15);
+        assert_eq!(layout.vertical, Mode::Smushing);
+        assert_eq!(layout.vertical_rules, 7936);
+        assert_eq!(
+            layout.to_string(),
+            "horizontal smushing (equal, underscore, hierarchy, pair), vertical smushing \
+             (equal, underscore, hierarchy, horizontal line, vertical line)"
+        );
+    }
+
+    #[test]
+    fn interprets_layout_modes() {
+        assert_eq!(LayoutOverride::from_mode(-2), None);
+        assert_eq!(LayoutOverride::from_mode(-1), Some(LayoutOverride::Full));
+        assert_eq!(LayoutOverride::from_mode(0), Some(LayoutOverride::Fitting));
+        assert_eq!(
+            LayoutOverride::from_mode(5),
+            Some(LayoutOverride::Smushing(5))
+        );
+    }
+
+    #[test]
+    fn overrides_one_direction_only() {
+        let font = Layout::from_header(0, Some(64));
+        let layout = font.with_horizontal(LayoutOverride::Smushing(1 | 32 | 64));
+        assert_eq!(layout.horizontal, Mode::Smushing);
+        assert_eq!(layout.horizontal_rules, 33);
+        assert_eq!(layout.vertical, font.vertical);
+        let layout = font.with_vertical(LayoutOverride::Smushing(16));
+        assert_eq!(layout.vertical, Mode::Smushing);
+        assert_eq!(layout.vertical_rules, VERTICAL_LINE);
+        assert_eq!(layout.horizontal, Mode::Fitting);
+        let layout = font.with_horizontal(LayoutOverride::ForceSmushing);
+        assert_eq!(layout.horizontal, Mode::Smushing);
+        assert_eq!(layout.horizontal_rules, 0);
+    }
+}
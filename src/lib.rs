@@ -0,0 +1,33 @@
+//! Rendering of FIGlet fonts, as used by the `figctl` command line tool.
+//!
+//! Load a [`Font`] with [`font::load`], configure a [`Renderer`] for it and render
+//! messages into [`Banner`]s.
+
+pub mod color;
+pub mod comment;
+pub mod control;
+pub mod error;
+pub mod figfont;
+pub mod filter;
+pub mod font;
+pub mod html;
+pub mod input;
+pub mod json;
+pub mod layout;
+pub mod png;
+pub mod search;
+pub mod svg;
+
+mod archive;
+mod banner;
+mod bitmap;
+mod bundled;
+mod render;
+mod smush;
+
+pub use banner::{Banner, OutputFormat, Placement};
+pub use error::FigctlError;
+pub use font::Font;
+pub use layout::LayoutOverride;
+pub use render::{Direction, Justification, Missing, Renderer, DEFAULT_WIDTH};
+pub use search::SearchPath;
@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use clap::Args;
+
+use figctl::layout::Layout;
+use figctl::{font, FigctlError, Font, Renderer, SearchPath};
+
+#[derive(Args, Debug)]
+pub struct ListFonts {
+    /// Text rendered as a preview of each font
+    #[arg(short, long, default_value = "Hello")]
+    sample: String,
+    /// Print the complete comment header instead of its first line
+    #[arg(long)]
+    comments: bool,
+}
+
+impl ListFonts {
+    /// Prints every font on the search path, followed by the built-in fonts that are
+    /// not shadowed by a font file, with their metadata and a preview.
+    pub fn run(&self, search_path: &SearchPath) -> Result<(), FigctlError> {
+        self.list(search_path).map_err(FigctlError::Output)
+    }
+
+    fn list(&self, search_path: &SearchPath) -> io::Result<()> {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        let mut listed = HashSet::new();
+        for path in search_path.fonts() {
+            match font::load_file(&path) {
+                Ok(font) => {
+                    listed.insert(font.name.clone());
+                    self.describe(&mut out, font)?;
+                }
+                Err(err) => writeln!(out, "{}\n  error: {}\n", path.display(), err)?,
+            }
+        }
+        for name in font::builtin_names().filter(|name| !listed.contains(*name)) {
+            if let Ok(font) = font::load(Some(name), search_path) {
+                self.describe(&mut out, font)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn describe(&self, out: &mut impl Write, font: Font) -> io::Result<()> {
+        let renderer = Renderer::new(font);
+        let font = renderer.font();
+        let header = &font.figfont.header;
+        writeln!(out, "{} ({})", font.name, font.origin())?;
+        writeln!(
+            out,
+            "  height {}, baseline {}, hardblank '{}', print direction {}",
+            header.height,
+            header.baseline,
+            header.hardblank,
+            match header.print_direction {
+                Some(1) => "right-to-left",
+                _ => "left-to-right",
+            }
+        )?;
+        writeln!(
+            out,
+            "  layout: {}",
+            Layout::from_header(header.old_layout, header.full_layout)
+        )?;
+        let comments = font.figfont.comments.trim_end();
+        if self.comments {
+            for line in comments.lines() {
+                writeln!(out, "  | {}", line)?;
+            }
+        } else if let Some(line) = comments.lines().find(|line| !line.trim().is_empty()) {
+            writeln!(out, "  | {}", line.trim())?;
+        }
+        match renderer.render(&self.sample) {
+            Ok(banner) => {
+                for row in banner.rows.iter().map(|row| row.trim_end()) {
+                    if row.is_empty() {
+                        writeln!(out)?;
+                    } else {
+                        writeln!(out, "    {}", row)?;
+                    }
+                }
+            }
+            Err(err) => writeln!(out, "    (no preview, {})", err)?,
+        }
+        writeln!(out)
+    }
+}
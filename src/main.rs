@@ -1,14 +1,515 @@
-use clap::{Parser};
-use figlet_rs::FIGfont;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+
+use figctl::color::{ColorDepth, Coloring, Rgb};
+use figctl::comment::{CommentOptions, CommentStyle};
+use figctl::control::{self, Control};
+use figctl::filter::Filter;
+use figctl::html::HtmlOptions;
+use figctl::input::{self, Source};
+use figctl::json::JsonOptions;
+use figctl::png::PngOptions;
+use figctl::svg::{SvgMode, SvgOptions};
+use figctl::{
+    font, Direction, FigctlError, Justification, LayoutOverride, Missing, OutputFormat, Renderer,
+    SearchPath,
+};
+use list::ListFonts;
+
+mod list;
 
 #[derive(Parser, Debug)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    after_help = "Exit status: 0 success, 2 invalid arguments, 3 font not found, \
+                  4 malformed font, 5 unsupported character, 6 unreadable file, \
+                  7 output error"
+)]
 struct FigletCtl {
-    message: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Font name or path to a FIGlet .flf or TOIlet .tlf font file, or a comma-separated
+    /// list of them to draw each character from the first font that has it
+    #[arg(short, long, value_name = "FONT[,FONT...]")]
+    font: Option<String>,
+    /// Additional directory to search for fonts, searched before $FIGLET_FONTDIR,
+    /// $XDG_DATA_HOME/figctl/fonts, /usr/share/figlet and the current directory
+    #[arg(short = 'd', long = "font-dir", value_name = "DIR", global = true)]
+    font_dirs: Vec<PathBuf>,
+    /// Report which font file was picked on stderr
+    #[arg(short, long)]
+    verbose: bool,
+    /// Read the message from a file instead of the command line
+    #[arg(long, value_name = "PATH", conflicts_with = "message")]
+    file: Option<PathBuf>,
+    /// Join consecutive lines into one banner, starting a new one only at blank lines,
+    /// instead of rendering every line as its own banner
+    #[arg(short, long)]
+    paragraph: bool,
+    /// Wrap the output at word boundaries to fit in this many columns, also the width
+    /// -c and -r justify within (80 when not given)
+    #[arg(short, long, value_name = "COLS")]
+    width: Option<usize>,
+    /// Wrap the output to the width of the terminal
+    #[arg(short = 't', long, conflicts_with = "width")]
+    terminal_width: bool,
+    /// Also break words that are too wide for a line of their own
+    #[arg(long)]
+    break_words: bool,
+    /// Justify the output left for left-to-right fonts and right otherwise (default)
+    #[arg(short = 'x', long, group = "justify")]
+    auto: bool,
+    /// Justify the output to the left
+    #[arg(short, long, group = "justify")]
+    left: bool,
+    /// Center the output within the output width
+    #[arg(short, long, group = "justify")]
+    center: bool,
+    /// Justify the output to the right of the output width
+    #[arg(short, long, group = "justify")]
+    right: bool,
+    /// Print direction: ltr, rtl or auto to follow the font's declared direction (default)
+    #[arg(long, value_name = "DIRECTION", group = "print_direction")]
+    direction: Option<Direction>,
+    /// Print left to right, same as --direction ltr
+    #[arg(short = 'L', group = "print_direction")]
+    left_to_right: bool,
+    /// Print right to left, same as --direction rtl
+    #[arg(short = 'R', group = "print_direction")]
+    right_to_left: bool,
+    /// Print in the font's declared direction, same as --direction auto
+    #[arg(short = 'X', group = "print_direction")]
+    font_direction: bool,
+    /// Join characters at full width, without fitting or smushing them together
+    #[arg(short = 'W', long, group = "horizontal")]
+    full_width: bool,
+    /// Move characters together until they touch (fitting, also called kerning)
+    #[arg(short, long, group = "horizontal")]
+    kerning: bool,
+    /// Smush characters with the font's rules even if it asks for fitting or full width
+    #[arg(short = 'S', long, group = "horizontal")]
+    force_smushing: bool,
+    /// Use the font's own horizontal layout (default)
+    #[arg(short, long, group = "horizontal")]
+    smushing: bool,
+    /// figlet layout mode: -1 full width, 0 fitting, a sum of smushing rules (1 equal,
+    /// 2 underscore, 4 hierarchy, 8 pair, 16 big X, 32 hardblank) or -2 for the font's own
+    #[arg(
+        short = 'm',
+        long,
+        value_name = "MODE",
+        allow_negative_numbers = true,
+        group = "horizontal"
+    )]
+    layout_mode: Option<i32>,
+    /// Vertical layout mode for stacked lines: -1 full height, 0 fitting, a sum of smushing
+    /// rules (1 equal, 2 underscore, 4 hierarchy, 8 horizontal line, 16 vertical line) or
+    /// -2 for the font's own
+    #[arg(short = 'M', long, value_name = "MODE", allow_negative_numbers = true)]
+    vertical_mode: Option<i32>,
+    /// figlet control file (.flc) translating characters or selecting the input encoding,
+    /// given by path or by name on the font search path; may be repeated and is applied
+    /// in order
+    #[arg(short = 'C', long = "control", value_name = "FILE")]
+    controls: Vec<String>,
+    /// What to do with characters the font lacks: error (default), skip them,
+    /// replace:CHAR to draw CHAR instead or fallback-font:NAME to draw them from another font
+    #[arg(long, value_name = "POLICY")]
+    missing: Option<MissingPolicy>,
+    /// Remove accents and other combining marks from the message, so that accented letters
+    /// render as their base letters with fonts that lack them
+    #[arg(long)]
+    strip_accents: bool,
+    /// Colour the banner: a colour name or #RRGGBB, rainbow, gradient:FROM:TO,
+    /// vertical-gradient:FROM:TO (both with as many colours as wanted) or palette:COLOR:...
+    /// to colour each character in turn. auto (default), always or never choose when to
+    /// write colours, auto only does on terminals without $NO_COLOR set
+    #[arg(long, value_name = "COLOR|WHEN")]
+    color: Vec<ColorArg>,
+    /// Post-process the banner with a filter: crop, flip, flop, rotate (180), left, right,
+    /// border, metal or gay (rainbow); may be repeated or given as a colon-separated list,
+    /// filters are applied in order
+    #[arg(
+        short = 'F',
+        long = "filter",
+        value_name = "FILTER",
+        value_delimiter = ':'
+    )]
+    filters: Vec<Filter>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Write a complete HTML document instead of a block to embed in a page
+    #[arg(long)]
+    standalone: bool,
+    /// Font family of HTML and SVG output (monospace for standalone documents and SVG)
+    #[arg(long, value_name = "FAMILY")]
+    font_family: Option<String>,
+    /// Background colour of HTML, SVG and PNG output, a colour name or #RRGGBB (white for
+    /// PNG)
+    #[arg(long, value_name = "COLOR")]
+    background: Option<Rgb>,
+    /// Leave the background of PNG output transparent
+    #[arg(long, conflicts_with = "background")]
+    transparent: bool,
+    /// Colour of SVG and PNG output where the banner is not coloured (black by default)
+    #[arg(long, value_name = "COLOR")]
+    foreground: Option<Rgb>,
+    /// Draw SVG output as one rectangle per filled cell instead of text, for fonts made
+    /// of block characters
+    #[arg(long)]
+    cells: bool,
+    /// Size in pixels of a cell one column wide in SVG output
+    #[arg(long, value_name = "WxH", default_value = "10x20")]
+    cell_size: CellSize,
+    /// Margin around SVG and PNG output, in pixels
+    #[arg(long, value_name = "PX", default_value_t = 0)]
+    padding: usize,
+    /// Size in pixels of every pixel of the bitmap font PNG output is drawn with
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    scale: u16,
+    /// Language whose comment syntax comment output is written in: rust, c, python, shell,
+    /// sql, yaml, html, lua, lisp, tex, vim and many others by name or file extension
+    #[arg(long, value_name = "LANG", required_if_eq("format", "comment"))]
+    lang: Option<CommentStyle>,
+    /// Draw a box of comment characters around comment output
+    #[arg(long = "box")]
+    comment_box: bool,
+    /// Write the output to this file instead of standard output
+    #[arg(short, long, value_name = "PATH")]
+    out: Option<PathBuf>,
+    /// Message to render, `-` or none at all to read it from standard input
+    message: Option<String>,
+}
+
+/// Value of `--missing`, which can also name a font to load.
+#[derive(Debug, Clone)]
+enum MissingPolicy {
+    Missing(Missing),
+    FallbackFont(String),
+}
+
+impl FromStr for MissingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<MissingPolicy, String> {
+        if let Some(name) = s.strip_prefix("fallback-font:") {
+            return Ok(MissingPolicy::FallbackFont(name.to_string()));
+        }
+        match s {
+            "error" | "skip" => s.parse().map(MissingPolicy::Missing),
+            _ if s.starts_with("replace:") => s.parse().map(MissingPolicy::Missing),
+            _ => Err(format!(
+                "unknown policy '{}', expected error, skip, replace:CHAR or fallback-font:NAME",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Plain text, with ANSI colours on terminals
+    Text,
+    /// An HTML <pre> block with inline colours
+    Html,
+    /// An SVG image
+    Svg,
+    /// A PNG image, which is not written to terminals
+    Png,
+    /// A JSON document with the lines and where every character was drawn
+    Json,
+    /// A comment block in the syntax of --lang, to put at the top of source files
+    Comment,
+}
+
+/// Value of `--cell-size`.
+#[derive(Debug, Clone, Copy)]
+struct CellSize {
+    width: usize,
+    height: usize,
+}
+
+impl FromStr for CellSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CellSize, String> {
+        let invalid = || format!("invalid cell size '{}', expected WIDTHxHEIGHT", s);
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        let size = |n: &str| n.parse().ok().filter(|&n| n > 0).ok_or_else(invalid);
+        Ok(CellSize {
+            width: size(width)?,
+            height: size(height)?,
+        })
+    }
+}
+
+/// When to write colours.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ColorWhen {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Value of `--color`, which is either a colouring or when to use it.
+#[derive(Debug, Clone)]
+enum ColorArg {
+    When(ColorWhen),
+    Coloring(Coloring),
+}
+
+impl FromStr for ColorArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ColorArg, String> {
+        match s {
+            "auto" => Ok(ColorArg::When(ColorWhen::Auto)),
+            "always" => Ok(ColorArg::When(ColorWhen::Always)),
+            "never" => Ok(ColorArg::When(ColorWhen::Never)),
+            _ => s.parse().map(ColorArg::Coloring),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List every font on the search path with its metadata and a preview
+    ListFonts(ListFonts),
 }
 
 fn main() {
     let args = FigletCtl::parse();
-    let standard_font = FIGfont::standard().unwrap();
-    let figure = standard_font.convert(args.message.as_str());
-    println!("{}", figure.unwrap());
+    if let Err(err) = run(args) {
+        // A closed pipe (`figctl list-fonts | head`) is not worth a message.
+        if !matches!(&err, FigctlError::Output(source) if source.kind() == io::ErrorKind::BrokenPipe)
+        {
+            eprintln!("figctl: {}", err);
+        }
+        process::exit(err.exit_code());
+    }
+}
+
+fn run(args: FigletCtl) -> Result<(), FigctlError> {
+    let search_path = SearchPath::new(&args.font_dirs);
+    if let Some(Command::ListFonts(list)) = &args.command {
+        return list.run(&search_path);
+    }
+    let source = match (args.file, args.message) {
+        (Some(path), _) => Source::File(path),
+        (None, Some(message)) if message == "-" => Source::Stdin,
+        (None, Some(message)) => Source::Text(message),
+        (None, None) if !io::stdin().is_terminal() => Source::Stdin,
+        (None, None) => FigletCtl::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "no message given, pass one as an argument, with --file or on standard input",
+            )
+            .exit(),
+    };
+    let specs: Vec<&str> = match &args.font {
+        Some(fonts) => fonts.split(',').collect(),
+        None => Vec::new(),
+    };
+    let font = font::load(specs.first().copied(), &search_path)?;
+    if args.verbose {
+        eprintln!("figctl: using font {}", font.origin());
+    }
+    let mut fallbacks = Vec::new();
+    for spec in specs.iter().skip(1) {
+        let fallback = font::load(Some(spec), &search_path)?;
+        if args.verbose {
+            eprintln!("figctl: using fallback font {}", fallback.origin());
+        }
+        fallbacks.push(fallback);
+    }
+    let mut control = Control::new();
+    for spec in &args.controls {
+        control.load(&control::find(spec, &search_path))?;
+    }
+    let mut renderer = Renderer::new(font)
+        .width(if args.terminal_width {
+            Some(terminal_width())
+        } else {
+            args.width
+        })
+        .break_words(args.break_words)
+        .justification(if args.left {
+            Justification::Left
+        } else if args.center {
+            Justification::Center
+        } else if args.right {
+            Justification::Right
+        } else {
+            Justification::Auto
+        })
+        .direction(if args.left_to_right {
+            Direction::LeftToRight
+        } else if args.right_to_left {
+            Direction::RightToLeft
+        } else {
+            args.direction.unwrap_or_default()
+        })
+        .horizontal_layout(if args.full_width {
+            Some(LayoutOverride::Full)
+        } else if args.kerning {
+            Some(LayoutOverride::Fitting)
+        } else if args.force_smushing {
+            Some(LayoutOverride::ForceSmushing)
+        } else {
+            args.layout_mode.and_then(LayoutOverride::from_mode)
+        })
+        .vertical_layout(args.vertical_mode.and_then(LayoutOverride::from_mode))
+        .control(control)
+        .strip_marks(args.strip_accents);
+    let mut when = ColorWhen::default();
+    let mut coloring = None;
+    for color in args.color {
+        match color {
+            ColorArg::When(value) => when = value,
+            ColorArg::Coloring(value) => coloring = Some(value),
+        }
+    }
+    let colored = coloring.is_some() || args.filters.iter().any(|filter| filter.colors());
+    for filter in args.filters {
+        renderer = renderer.filter(filter);
+    }
+    if args.format == Format::Png && args.out.is_none() && io::stdout().is_terminal() {
+        FigletCtl::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "refusing to write a PNG image to a terminal, pass --out or redirect the output",
+            )
+            .exit();
+    }
+    match args.missing {
+        Some(MissingPolicy::Missing(missing)) => renderer = renderer.missing(missing),
+        Some(MissingPolicy::FallbackFont(name)) => {
+            let fallback = font::load(Some(&name), &search_path)?;
+            if args.verbose {
+                eprintln!("figctl: using fallback font {}", fallback.origin());
+            }
+            fallbacks.push(fallback);
+        }
+        None => {}
+    }
+    for fallback in fallbacks {
+        renderer = renderer.fallback(fallback);
+    }
+    let text = source.read()?;
+    let banners = input::banners(&text, args.paragraph);
+    if banners.is_empty() {
+        return Ok(());
+    }
+    // Banners become lines of one figure, so they are stacked by the vertical layout.
+    let message = banners.join(&b'\n');
+    // Colours are written to terminals that support them, and always to formats that
+    // have no other use.
+    let (format, use_colors) = match args.format {
+        Format::Text => match color_depth(when) {
+            Some(depth) if colored => (OutputFormat::Ansi(depth), true),
+            _ => (OutputFormat::Text, false),
+        },
+        Format::Html => (
+            OutputFormat::Html(HtmlOptions {
+                standalone: args.standalone,
+                font_family: args.font_family,
+                background: args.background,
+            }),
+            when != ColorWhen::Never,
+        ),
+        Format::Svg => (
+            OutputFormat::Svg(SvgOptions {
+                mode: if args.cells {
+                    SvgMode::Cells
+                } else {
+                    SvgMode::Text
+                },
+                font_family: args.font_family,
+                foreground: args.foreground,
+                background: args.background,
+                padding: args.padding,
+                cell_width: args.cell_size.width,
+                cell_height: args.cell_size.height,
+            }),
+            when != ColorWhen::Never,
+        ),
+        Format::Png => (
+            OutputFormat::Png(PngOptions {
+                scale: args.scale.into(),
+                foreground: args.foreground,
+                background: args.background,
+                transparent: args.transparent,
+                padding: args.padding,
+            }),
+            when != ColorWhen::Never,
+        ),
+        Format::Json => (
+            OutputFormat::Json(JsonOptions {
+                font: renderer.font().name.clone(),
+                font_path: renderer.font().path.clone(),
+                font_height: renderer.font().figfont.header.height,
+                layout: renderer.layout(),
+            }),
+            false,
+        ),
+        Format::Comment => (
+            OutputFormat::Comment(CommentOptions {
+                style: args.lang.expect("--lang is required with --format comment"),
+                border: args.comment_box,
+            }),
+            false,
+        ),
+    };
+    renderer = renderer.coloring(coloring).output_format(format);
+    let mut banner = renderer.render_bytes(&message)?;
+    if !use_colors {
+        banner.colors.clear();
+    }
+    match args.out {
+        Some(path) => {
+            let mut out = BufWriter::new(File::create(path).map_err(FigctlError::Output)?);
+            banner.write(&mut out).and_then(|()| out.flush())
+        }
+        None => banner.write(&mut io::stdout().lock()),
+    }
+    .map_err(FigctlError::Output)
+}
+
+/// Colours to write escape sequences for, `None` for plain text. Without a terminal that
+/// is known to support colour, `always` still writes the 16 ANSI colours.
+fn color_depth(when: ColorWhen) -> Option<ColorDepth> {
+    let var = |name| std::env::var(name).ok();
+    let detected = ColorDepth::detect(var("COLORTERM").as_deref(), var("TERM").as_deref());
+    match when {
+        ColorWhen::Never => None,
+        ColorWhen::Always => Some(detected.unwrap_or(ColorDepth::Basic)),
+        ColorWhen::Auto => {
+            let no_color = var("NO_COLOR").is_some_and(|value| !value.is_empty());
+            if no_color || !io::stdout().is_terminal() {
+                None
+            } else {
+                detected
+            }
+        }
+    }
+}
+
+/// Width of the terminal on standard output, or figlet's default width when it is not a
+/// terminal.
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| width as usize)
+        .unwrap_or(figctl::DEFAULT_WIDTH)
 }
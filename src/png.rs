@@ -0,0 +1,174 @@
+//! PNG output: the banner rasterized with the built-in bitmap font.
+//!
+//! Every cell is drawn from the bitmap font at its natural size of 6x8 pixels and then
+//! enlarged by the scale factor, so the image is as sharp as the font at any size. The
+//! image is RGBA, with the background either opaque or fully transparent.
+
+use std::io::{self, Write};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::banner::Banner;
+use crate::bitmap::{self, CELL_HEIGHT, CELL_WIDTH};
+use crate::color::Rgb;
+
+/// Options of the PNG output format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PngOptions {
+    /// Size in pixels of a pixel of the bitmap font, at least 1.
+    pub scale: usize,
+    /// Colour of sub-characters the banner does not colour, black when `None`.
+    pub foreground: Option<Rgb>,
+    /// Colour filling the whole image, white when `None`.
+    pub background: Option<Rgb>,
+    /// Leave the background transparent instead of filling it.
+    pub transparent: bool,
+    /// Margin around the banner, in pixels of the image.
+    pub padding: usize,
+}
+
+impl Default for PngOptions {
+    fn default() -> PngOptions {
+        PngOptions {
+            scale: 2,
+            foreground: None,
+            background: None,
+            transparent: false,
+            padding: 0,
+        }
+    }
+}
+
+/// Bytes of a pixel of the image.
+const CHANNELS: usize = 4;
+
+pub(crate) fn write(banner: &Banner, options: &PngOptions, out: &mut impl Write) -> io::Result<()> {
+    let scale = options.scale.max(1);
+    let width = banner.width() * CELL_WIDTH * scale + 2 * options.padding;
+    let height = banner.height() * CELL_HEIGHT * scale + 2 * options.padding;
+    let background = options.background.unwrap_or(Rgb::new(255, 255, 255));
+    let alpha = if options.transparent { 0 } else { 255 };
+    let mut image = [background.r, background.g, background.b, alpha].repeat(width * height);
+    let foreground = options.foreground.unwrap_or(Rgb::new(0, 0, 0));
+    for row in 0..banner.height() {
+        for (column, c) in banner.cells(row) {
+            let cells = c.width().unwrap_or(0);
+            if c == ' ' || cells == 0 {
+                continue;
+            }
+            let color = banner.color(row, column).unwrap_or(foreground);
+            let pixel = [color.r, color.g, color.b, 255];
+            for y in 0..CELL_HEIGHT {
+                for x in 0..cells * CELL_WIDTH {
+                    if !bitmap::pixel(c, cells, x, y) {
+                        continue;
+                    }
+                    let left = options.padding + (column * CELL_WIDTH + x) * scale;
+                    let top = options.padding + (row * CELL_HEIGHT + y) * scale;
+                    for line in top..top + scale {
+                        let start = (line * width + left) * CHANNELS;
+                        for chunk in image[start..start + scale * CHANNELS].chunks_mut(CHANNELS) {
+                            chunk.copy_from_slice(&pixel);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let size = |n: usize| {
+        u32::try_from(n).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "image too large"))
+    };
+    let mut encoder = ::png::Encoder::new(out, size(width)?, size(height)?);
+    encoder.set_color(::png::ColorType::Rgba);
+    encoder.set_depth(::png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io_error)?;
+    writer.write_image_data(&image).map_err(io_error)?;
+    writer.finish().map_err(io_error)
+}
+
+fn io_error(err: ::png::EncodingError) -> io::Error {
+    match err {
+        ::png::EncodingError::IoError(err) => err,
+        err => io::Error::other(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::OutputFormat;
+
+    /// Encodes `rows` and decodes them again, returning the size and RGBA pixels.
+    fn png(
+        rows: &[&str],
+        colors: Vec<Vec<Option<Rgb>>>,
+        options: PngOptions,
+    ) -> (u32, u32, Vec<u8>) {
+        let banner = Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: Vec::new(),
+            colors,
+            format: OutputFormat::Png(options),
+        };
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        let mut reader = ::png::Decoder::new(&out[..]).read_info().unwrap();
+        let mut image = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut image).unwrap();
+        assert_eq!(info.color_type, ::png::ColorType::Rgba);
+        (info.width, info.height, image)
+    }
+
+    fn at(image: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let start = ((y * width + x) * 4) as usize;
+        image[start..start + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn rasterizes_cells_with_the_bitmap_font() {
+        let options = PngOptions {
+            scale: 1,
+            padding: 1,
+            ..PngOptions::default()
+        };
+        let (width, height, image) = png(&["|_", "全"], Vec::new(), options);
+        assert_eq!((width, height), (14, 18));
+        let (black, white) = ([0, 0, 0, 255], [255, 255, 255, 255]);
+        // The bar runs down the third column of the first cell.
+        for y in 1..9 {
+            assert_eq!(at(&image, width, 3, y), black);
+            assert_eq!(at(&image, width, 2, y), white);
+        }
+        // The underscore fills the bottom row of the second cell.
+        for x in 7..13 {
+            assert_eq!(at(&image, width, x, 8), black);
+            assert_eq!(at(&image, width, x, 7), white);
+        }
+        // The wide character is an empty box two cells wide.
+        assert_eq!(at(&image, width, 1, 9), black);
+        assert_eq!(at(&image, width, 11, 9), black);
+        assert_eq!(at(&image, width, 12, 9), white);
+        assert_eq!(at(&image, width, 0, 0), white);
+    }
+
+    #[test]
+    fn scales_and_colours_pixels() {
+        let red = Some(Rgb::new(255, 0, 0));
+        let options = PngOptions {
+            scale: 3,
+            foreground: Some(Rgb::new(0, 0, 255)),
+            transparent: true,
+            ..PngOptions::default()
+        };
+        let (width, height, image) = png(&["█|"], vec![vec![red]], options);
+        assert_eq!((width, height), (36, 24));
+        assert_eq!(at(&image, width, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(at(&image, width, 17, 23), [255, 0, 0, 255]);
+        // The bar is three pixels wide at the third column of its cell.
+        assert_eq!(at(&image, width, 23, 0)[3], 0);
+        assert_eq!(at(&image, width, 24, 0), [0, 0, 255, 255]);
+        assert_eq!(at(&image, width, 26, 23), [0, 0, 255, 255]);
+        assert_eq!(at(&image, width, 27, 0)[3], 0);
+    }
+}
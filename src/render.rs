@@ -0,0 +1,1073 @@
+use std::iter;
+use std::str::FromStr;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_width::UnicodeWidthChar;
+
+use crate::banner::{Banner, OutputFormat, Placement};
+use crate::color::Coloring;
+use crate::control::Control;
+use crate::error::FigctlError;
+use crate::filter::Filter;
+use crate::font::Font;
+use crate::layout::{Layout, LayoutOverride, Mode, VERTICAL_LINE};
+use crate::smush;
+
+/// Width lines are justified within when no output width was given, figlet's default.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Stands in for the second column of a double-width sub-character, so that every cell of
+/// a row is one column wide.
+pub(crate) const CONTINUATION: char = '\0';
+
+/// Stands in for the hardblanks of every font while composing, so that the hardblank of
+/// one font is never confused with a visible sub-character of another.
+const HARDBLANK: char = '\u{1}';
+
+const NEWLINE: i64 = '\n' as i64;
+const SPACE: i64 = ' ' as i64;
+const TAB: i64 = '\t' as i64;
+
+/// Where each FIG line is placed within the output width.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Justification {
+    /// Left for left-to-right text, right for right-to-left text.
+    #[default]
+    Auto,
+    Left,
+    Center,
+    Right,
+}
+
+/// Direction FIGcharacters are laid out in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// The print direction declared by the font.
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Direction, String> {
+        match s {
+            "auto" => Ok(Direction::Auto),
+            "ltr" => Ok(Direction::LeftToRight),
+            "rtl" => Ok(Direction::RightToLeft),
+            _ => Err(format!(
+                "unknown direction '{}', expected ltr, rtl or auto",
+                s
+            )),
+        }
+    }
+}
+
+/// What to do with a character that neither the font nor any fallback font has.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Missing {
+    /// Fail with [`FigctlError::UnsupportedCharacter`].
+    #[default]
+    Error,
+    /// Leave the character out.
+    Skip,
+    /// Draw this character instead, which the fonts have to have.
+    Replace(char),
+}
+
+impl FromStr for Missing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Missing, String> {
+        match s {
+            "error" => return Ok(Missing::Error),
+            "skip" => return Ok(Missing::Skip),
+            _ => {}
+        }
+        let mut replacement = s.strip_prefix("replace:").map(str::chars);
+        match replacement
+            .as_mut()
+            .map(|chars| (chars.next(), chars.next()))
+        {
+            Some((Some(c), None)) => Ok(Missing::Replace(c)),
+            Some(_) => Err(format!(
+                "'{}' does not give exactly one replacement character",
+                s
+            )),
+            None => Err(format!(
+                "unknown policy '{}', expected error, skip or replace:CHAR",
+                s
+            )),
+        }
+    }
+}
+
+/// Options controlling how a message is laid out.
+#[derive(Debug, Clone, Default)]
+struct Options {
+    /// Width of the output in columns, of which lines use all but the last, `None` for
+    /// no limit.
+    width: Option<usize>,
+    /// Also break words that are wider than `width` on their own, like figlet does.
+    break_words: bool,
+    justification: Justification,
+    direction: Direction,
+    /// Horizontal layout to use instead of the one declared by the font.
+    horizontal: Option<LayoutOverride>,
+    /// Vertical layout to use instead of the one declared by the font.
+    vertical: Option<LayoutOverride>,
+    missing: Missing,
+    coloring: Option<Coloring>,
+    filters: Vec<Filter>,
+    /// Remove combining marks after decomposing the message, leaving accented letters
+    /// as their base letters.
+    strip_marks: bool,
+    format: OutputFormat,
+}
+
+/// Renders messages with a font. Every setting defaults to figlet's behaviour and the
+/// font's own layout, and can be changed builder-style before rendering.
+pub struct Renderer {
+    font: Font,
+    /// Fonts to draw the characters `font` lacks from, in order.
+    fallbacks: Vec<Font>,
+    control: Control,
+    options: Options,
+}
+
+impl Renderer {
+    pub fn new(font: Font) -> Renderer {
+        Renderer {
+            font,
+            fallbacks: Vec::new(),
+            control: Control::default(),
+            options: Options::default(),
+        }
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Layout the font is rendered with, its own with the requested overrides applied.
+    pub fn layout(&self) -> Layout {
+        layout(&self.font, &self.options)
+    }
+
+    /// Wraps the output at word boundaries to fit in `width` columns. Like figlet, lines
+    /// are kept and justified within one column less ([`DEFAULT_WIDTH`] when `None`).
+    pub fn width(mut self, width: Option<usize>) -> Renderer {
+        self.options.width = width;
+        self
+    }
+
+    /// Also breaks words that are too wide for a line of their own.
+    pub fn break_words(mut self, break_words: bool) -> Renderer {
+        self.options.break_words = break_words;
+        self
+    }
+
+    pub fn justification(mut self, justification: Justification) -> Renderer {
+        self.options.justification = justification;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Renderer {
+        self.options.direction = direction;
+        self
+    }
+
+    /// Joins characters with `layout` instead of the font's horizontal layout.
+    pub fn horizontal_layout(mut self, layout: Option<LayoutOverride>) -> Renderer {
+        self.options.horizontal = layout;
+        self
+    }
+
+    /// Stacks lines with `layout` instead of the font's vertical layout.
+    pub fn vertical_layout(mut self, layout: Option<LayoutOverride>) -> Renderer {
+        self.options.vertical = layout;
+        self
+    }
+
+    /// Colours the rendered banners with `coloring`, which only shows in output formats
+    /// that support colour.
+    pub fn coloring(mut self, coloring: Option<Coloring>) -> Renderer {
+        self.options.coloring = coloring;
+        self
+    }
+
+    /// Post-processes the rendered banners with `filter`, after colouring them and after
+    /// the filters added before.
+    pub fn filter(mut self, filter: Filter) -> Renderer {
+        self.options.filters.push(filter);
+        self
+    }
+
+    pub fn output_format(mut self, format: OutputFormat) -> Renderer {
+        self.options.format = format;
+        self
+    }
+
+    /// Draws the characters that neither the font nor any earlier fallback has from
+    /// `font`. FIGcharacters of fonts with different heights are aligned on their
+    /// baselines.
+    pub fn fallback(mut self, font: Font) -> Renderer {
+        self.fallbacks.push(font);
+        self
+    }
+
+    /// Sets what to do with characters that none of the fonts has.
+    pub fn missing(mut self, missing: Missing) -> Renderer {
+        self.options.missing = missing;
+        self
+    }
+
+    /// Removes accents and other combining marks from messages, so that accented letters
+    /// render as their base letters with fonts that lack them.
+    pub fn strip_marks(mut self, strip_marks: bool) -> Renderer {
+        self.options.strip_marks = strip_marks;
+        self
+    }
+
+    /// Translates characters and decodes input with `control` before rendering.
+    pub fn control(mut self, control: Control) -> Renderer {
+        self.control = control;
+        self
+    }
+
+    /// Renders `message`, every line of it starting a new FIG line. Messages are normalized
+    /// to NFC first, so that a letter followed by a combining accent is drawn with the
+    /// font's precomposed character.
+    pub fn render(&self, message: &str) -> Result<Banner, FigctlError> {
+        let codes: Vec<i64> = message.chars().map(|c| c as i64).collect();
+        self.render_codes(&codes)
+    }
+
+    /// Renders `message` after decoding it in the input mode of the control files.
+    pub fn render_bytes(&self, message: &[u8]) -> Result<Banner, FigctlError> {
+        let codes = self
+            .control
+            .decode(message)
+            .map_err(FigctlError::Encoding)?;
+        self.render_codes(&codes)
+    }
+
+    fn render_codes(&self, codes: &[i64]) -> Result<Banner, FigctlError> {
+        // Like figlet, tabs are spaces rather than characters of their own.
+        let codes: Vec<i64> = codes
+            .iter()
+            .map(|&code| if code == TAB { SPACE } else { code })
+            .collect();
+        let normalized = normalize(&codes, self.options.strip_marks);
+        let codes: Vec<i64> = normalized
+            .iter()
+            .map(|&code| self.control.map(code))
+            .collect();
+        let fonts: Vec<&Font> = iter::once(&self.font).chain(&self.fallbacks).collect();
+        let mut banner = render(&fonts, &codes, &self.options)?;
+        banner.text = normalized
+            .iter()
+            .map(|&code| {
+                u32::try_from(code)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect();
+        if let Some(coloring) = &self.options.coloring {
+            banner.colors = coloring.paint(&banner);
+        }
+        for filter in &self.options.filters {
+            banner = filter.apply(banner);
+        }
+        Ok(banner)
+    }
+}
+
+/// Normalizes the Unicode characters among `codes` to NFC, removing combining marks first
+/// if `strip_marks` is set. Codes that are not Unicode scalar values, as decoded by some
+/// control files, are kept as they are and split the text around them.
+fn normalize(codes: &[i64], strip_marks: bool) -> Vec<i64> {
+    fn flush(run: &mut String, normalized: &mut Vec<i64>, strip_marks: bool) {
+        if strip_marks {
+            let stripped = run.nfd().filter(|&c| !is_combining_mark(c));
+            normalized.extend(stripped.nfc().map(|c| c as i64));
+        } else {
+            normalized.extend(run.nfc().map(|c| c as i64));
+        }
+        run.clear();
+    }
+
+    let mut normalized = Vec::with_capacity(codes.len());
+    let mut run = String::new();
+    for &code in codes {
+        match u32::try_from(code).ok().and_then(char::from_u32) {
+            Some(c) => run.push(c),
+            None => {
+                flush(&mut run, &mut normalized, strip_marks);
+                normalized.push(code);
+            }
+        }
+    }
+    flush(&mut run, &mut normalized, strip_marks);
+    normalized
+}
+
+/// A FIGcharacter as a grid of sub-characters, every row `width` wide, for the character
+/// at `index` in the message.
+struct Glyph {
+    rows: Vec<Vec<char>>,
+    width: usize,
+    index: usize,
+    code: i64,
+}
+
+/// One line of FIGcharacters, `height` rows tall.
+#[derive(Debug, Clone)]
+struct Line {
+    rows: Vec<Vec<char>>,
+    width: usize,
+    characters: usize,
+    /// Width of the last FIGcharacter added, smushing needs both sides to be at least two
+    /// columns wide.
+    previous_width: usize,
+    /// Where each FIGcharacter ended up, within the line.
+    placements: Vec<Placement>,
+}
+
+impl Line {
+    fn new(height: usize) -> Line {
+        Line {
+            rows: vec![Vec::new(); height],
+            width: 0,
+            characters: 0,
+            previous_width: 0,
+            placements: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.characters == 0
+    }
+}
+
+/// Joins FIGcharacters horizontally according to a layout mode and its smushing rules,
+/// the way figlet's `smushamt` and `addchar` do. Right-to-left, every FIGcharacter is
+/// added on the left of the line instead of the right.
+struct Composer {
+    mode: Mode,
+    rules: i32,
+    hardblank: char,
+    right_to_left: bool,
+}
+
+impl Composer {
+    fn push(&self, line: &mut Line, glyph: &Glyph) {
+        let widths = (line.previous_width, glyph.width);
+        let rows = line.rows.iter().zip(&glyph.rows);
+        let pairs: Vec<(&[char], &[char])> = if self.right_to_left {
+            rows.map(|(row, glyph_row)| (&glyph_row[..], &row[..]))
+                .collect()
+        } else {
+            rows.map(|(row, glyph_row)| (&row[..], &glyph_row[..]))
+                .collect()
+        };
+        let amount = self.overlap(&pairs, glyph.width, widths);
+        line.rows = pairs
+            .into_iter()
+            .map(|(left, right)| self.join(left, right, amount, widths))
+            .collect();
+        // Right-to-left, the FIGcharacters already on the line move right instead. The
+        // blank columns a first FIGcharacter loses are not part of its placement.
+        let start = if self.right_to_left {
+            let shift = glyph.width - amount;
+            for placement in &mut line.placements {
+                placement.columns = placement.columns.start + shift..placement.columns.end + shift;
+            }
+            0
+        } else {
+            line.width as isize - amount as isize
+        };
+        line.width = line.width + glyph.width - amount;
+        let end = (start + glyph.width as isize).clamp(0, line.width as isize) as usize;
+        line.placements.push(Placement {
+            index: glyph.index,
+            code: glyph.code,
+            rows: 0..glyph.rows.len(),
+            columns: start.max(0) as usize..end,
+        });
+        line.characters += 1;
+        line.previous_width = glyph.width;
+    }
+
+    /// Number of columns the right rows can be moved into the left ones, at most `limit`,
+    /// the width of the FIGcharacter being added.
+    fn overlap(&self, pairs: &[(&[char], &[char])], limit: usize, widths: (usize, usize)) -> usize {
+        if self.mode == Mode::FullWidth {
+            return 0;
+        }
+        let mut amount = limit;
+        for (left_row, right_row) in pairs {
+            let (end, left) = match left_row.iter().rposition(|&c| c != ' ') {
+                Some(end) => (end, Some(left_row[end])),
+                None => (0, None),
+            };
+            let (start, right) = match right_row.iter().position(|&c| c != ' ') {
+                Some(start) => (start, Some(right_row[start])),
+                None => (right_row.len(), None),
+            };
+            let mut row_amount = (start + left_row.len()) as isize - 1 - end as isize;
+            match (left, right) {
+                (None, _) => row_amount += 1,
+                (Some(left), Some(right)) if self.smush(left, right, widths).is_some() => {
+                    row_amount += 1
+                }
+                _ => {}
+            }
+            amount = amount.min(row_amount.max(0) as usize);
+        }
+        amount
+    }
+
+    /// Joins two rows overlapping by `amount` columns. Columns of either row that end up
+    /// outside the result are blank, they are dropped like figlet does.
+    fn join(
+        &self,
+        left: &[char],
+        right: &[char],
+        amount: usize,
+        widths: (usize, usize),
+    ) -> Vec<char> {
+        let offset = left.len() as isize - amount as isize;
+        let width = (left.len() + right.len()).saturating_sub(amount);
+        (0..width)
+            .map(|column| {
+                let right_column = column as isize - offset;
+                let from_left = left.get(column).copied();
+                let from_right = usize::try_from(right_column)
+                    .ok()
+                    .and_then(|column| right.get(column).copied());
+                match (from_left, from_right) {
+                    (Some(l), Some(r)) => self.smush(l, r, widths).unwrap_or(r),
+                    (Some(c), None) | (None, Some(c)) => c,
+                    (None, None) => ' ',
+                }
+            })
+            .collect()
+    }
+
+    /// Smushes two sub-characters, `widths` being those of the FIGcharacter already on the
+    /// line and of the one being added.
+    fn smush(&self, left: char, right: char, widths: (usize, usize)) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+        if self.mode != Mode::Smushing || widths.0 < 2 || widths.1 < 2 {
+            return None;
+        }
+        if is_wide(left) || is_wide(right) {
+            return None;
+        }
+        smush::horizontal(left, right, self.rules, self.hardblank, self.right_to_left)
+    }
+}
+
+/// Stacks FIG lines on top of each other according to the vertical layout mode and its
+/// smushing rules, as described by the FIGfont 2.2 spec.
+struct Stacker {
+    mode: Mode,
+    rules: i32,
+    hardblank: char,
+}
+
+/// Outcome of overlapping two rows of sub-characters.
+enum Overlap {
+    /// Every column has a blank on at least one side, or supersmushes vertical lines.
+    Clear,
+    /// Some column smushes, so the lines cannot move any closer.
+    Last,
+    Blocked,
+}
+
+impl Stacker {
+    /// Adds `lower` below `rows` and returns the row it starts at.
+    fn push(&self, rows: &mut Vec<Vec<char>>, lower: Vec<Vec<char>>) -> usize {
+        let amount = self.overlap(rows, &lower);
+        let start = rows.len() - amount;
+        for (upper, lower) in rows[start..].iter_mut().zip(&lower) {
+            upper.resize(upper.len().max(lower.len()), ' ');
+            for (cell, &below) in upper.iter_mut().zip(lower) {
+                *cell = match (*cell, below) {
+                    (above, ' ') => above,
+                    (' ', below) => below,
+                    (above, below) => self.smush(above, below).unwrap_or(below),
+                };
+            }
+        }
+        rows.extend(lower.into_iter().skip(amount));
+        start
+    }
+
+    /// Number of rows `lower` can be moved up into `upper`.
+    fn overlap(&self, upper: &[Vec<char>], lower: &[Vec<char>]) -> usize {
+        if self.mode == Mode::FullWidth {
+            return 0;
+        }
+        let limit = upper.len().min(lower.len());
+        for amount in 1..=limit {
+            let rows = upper[upper.len() - amount..].iter().zip(lower);
+            let mut last = false;
+            for (above, below) in rows {
+                match self.overlap_row(above, below) {
+                    Overlap::Clear => {}
+                    Overlap::Last => last = true,
+                    Overlap::Blocked => return amount - 1,
+                }
+            }
+            if last {
+                return amount;
+            }
+        }
+        limit
+    }
+
+    fn overlap_row(&self, above: &[char], below: &[char]) -> Overlap {
+        let mut result = Overlap::Clear;
+        for (&upper, &lower) in above.iter().zip(below) {
+            if upper == ' ' || lower == ' ' {
+                continue;
+            }
+            if self.mode != Mode::Smushing {
+                return Overlap::Blocked;
+            }
+            if self.rules & VERTICAL_LINE != 0 && upper == '|' && lower == '|' {
+                continue;
+            }
+            match self.smush(upper, lower) {
+                Some(_) => result = Overlap::Last,
+                None => return Overlap::Blocked,
+            }
+        }
+        result
+    }
+
+    fn smush(&self, upper: char, lower: char) -> Option<char> {
+        if is_wide(upper) || is_wide(lower) {
+            return None;
+        }
+        smush::vertical(upper, lower, self.rules, self.hardblank)
+    }
+}
+
+/// Renders `message` with the first of `fonts`, wrapping every line of it at word
+/// boundaries into as many FIG lines as needed to stay within `options.width`, and returns
+/// the justified output rows with the FIG lines stacked according to the vertical layout.
+///
+/// Characters the first font lacks are drawn from the first of the others that has them,
+/// and otherwise handled according to `options.missing`. The layout is always that of the
+/// first font, while hardblanks are those of the font each FIGcharacter comes from.
+/// FIGcharacters from fonts of different heights are
+/// aligned on their baselines, so FIG lines are as tall as needed to fit the fonts used.
+fn render(fonts: &[&Font], message: &[i64], options: &Options) -> Result<Banner, FigctlError> {
+    let font = fonts[0];
+    let header = &font.figfont.header;
+    let layout = layout(font, options);
+    let right_to_left = match options.direction {
+        Direction::Auto => header.print_direction == Some(1),
+        direction => direction == Direction::RightToLeft,
+    };
+    let composer = Composer {
+        mode: layout.horizontal,
+        rules: layout.horizontal_rules,
+        hardblank: HARDBLANK,
+        right_to_left,
+    };
+    let unsupported = |code| FigctlError::UnsupportedCharacter {
+        code,
+        font: font.name.clone(),
+    };
+    let defines = |code| {
+        fonts
+            .iter()
+            .find_map(|font| Some((*font, font.figfont.characters.get(&code)?)))
+    };
+    let mut resolved = Vec::with_capacity(message.len());
+    for (index, &code) in message.iter().enumerate() {
+        match options.missing {
+            _ if code == NEWLINE || defines(code).is_some() => resolved.push((index, code)),
+            Missing::Error => return Err(unsupported(code)),
+            Missing::Skip => {}
+            Missing::Replace(replacement) => resolved.push((index, replacement as i64)),
+        }
+    }
+    let mut used = vec![font];
+    for &(_, code) in &resolved {
+        if let Some((source, _)) = defines(code) {
+            if !used.iter().any(|font| std::ptr::eq(*font, source)) {
+                used.push(source);
+            }
+        }
+    }
+    let above = used.iter().map(|font| baseline(font)).max().unwrap_or(0);
+    let below = used
+        .iter()
+        .map(|font| font.figfont.header.height - baseline(font))
+        .max()
+        .unwrap_or(0);
+    let height = above + below;
+
+    let glyph = |(index, code): (usize, i64)| {
+        let (source, figchar) = defines(code).ok_or_else(|| unsupported(code))?;
+        let hardblank = source.figfont.header.hardblank;
+        let padding = iter::repeat_n(Vec::new(), above - baseline(source));
+        let mut rows: Vec<Vec<char>> = padding
+            .chain(figchar.iter().map(|row| {
+                let mut cells = cells(row);
+                for cell in cells.iter_mut().filter(|cell| **cell == hardblank) {
+                    *cell = HARDBLANK;
+                }
+                cells
+            }))
+            .collect();
+        rows.resize(height, Vec::new());
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        rows.iter_mut().for_each(|row| row.resize(width, ' '));
+        Ok(Glyph {
+            rows,
+            width,
+            index,
+            code,
+        })
+    };
+
+    let mut lines = Vec::new();
+    for text in resolved.split(|&(_, code)| code == NEWLINE) {
+        wrap(text, &composer, &glyph, height, options, &mut lines)?;
+    }
+
+    let justification = match options.justification {
+        Justification::Auto if right_to_left => Justification::Right,
+        Justification::Auto => Justification::Left,
+        justification => justification,
+    };
+    let width = options.width.unwrap_or(DEFAULT_WIDTH).saturating_sub(1);
+    let stacker = Stacker {
+        mode: layout.vertical,
+        rules: layout.vertical_rules,
+        hardblank: HARDBLANK,
+    };
+    let mut rows = Vec::new();
+    let mut placements = Vec::new();
+    for line in lines {
+        let padding = match justification {
+            Justification::Center => width.saturating_sub(line.width) / 2,
+            Justification::Right => width.saturating_sub(line.width),
+            _ => 0,
+        };
+        let indented = line
+            .rows
+            .into_iter()
+            .map(|row| iter::repeat_n(' ', padding).chain(row).collect())
+            .collect();
+        let start = stacker.push(&mut rows, indented);
+        placements.extend(line.placements.into_iter().map(|placement| Placement {
+            rows: placement.rows.start + start..placement.rows.end + start,
+            columns: placement.columns.start + padding..placement.columns.end + padding,
+            ..placement
+        }));
+    }
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .filter(|&c| c != CONTINUATION)
+                .map(|c| if c == HARDBLANK { ' ' } else { c })
+                .collect()
+        })
+        .collect();
+    Ok(Banner {
+        rows,
+        text: String::new(),
+        placements,
+        colors: Vec::new(),
+        format: options.format.clone(),
+    })
+}
+
+/// Layout declared by `font` with the overrides of `options` applied.
+fn layout(font: &Font, options: &Options) -> Layout {
+    let header = &font.figfont.header;
+    let mut layout = Layout::from_header(header.old_layout, header.full_layout);
+    if let Some(horizontal) = options.horizontal {
+        layout = layout.with_horizontal(horizontal);
+    }
+    if let Some(vertical) = options.vertical {
+        layout = layout.with_vertical(vertical);
+    }
+    layout
+}
+
+/// Rows from the top of the FIGcharacters of `font` to their baseline, which fonts do not
+/// always declare within their height.
+fn baseline(font: &Font) -> usize {
+    let header = &font.figfont.header;
+    header.baseline.clamp(1, header.height)
+}
+
+/// Splits a row of a FIGcharacter into cells one column wide. Double-width sub-characters
+/// take up a second [`CONTINUATION`] cell, zero-width ones have no column of their own and
+/// are dropped.
+fn cells(row: &str) -> Vec<char> {
+    let mut cells = Vec::with_capacity(row.len());
+    for c in row.chars() {
+        match c.width() {
+            Some(0) => {}
+            Some(2) => cells.extend([c, CONTINUATION]),
+            _ => cells.push(c),
+        }
+    }
+    cells
+}
+
+/// Whether `c` is one half of a double-width sub-character, which is never smushed as the
+/// other half would be left behind.
+fn is_wide(c: char) -> bool {
+    c == CONTINUATION || c.width() == Some(2)
+}
+
+/// Lays out one line of the message, appending the FIG lines it wraps into to `lines`.
+fn wrap(
+    text: &[(usize, i64)],
+    composer: &Composer,
+    glyph: &impl Fn((usize, i64)) -> Result<Glyph, FigctlError>,
+    height: usize,
+    options: &Options,
+    lines: &mut Vec<Line>,
+) -> Result<(), FigctlError> {
+    let limit = options
+        .width
+        .map_or(usize::MAX, |width| width.saturating_sub(1));
+    let first = lines.len();
+    let mut line = Line::new(height);
+    // Position of the current word in `text`, the space before it is the code just before.
+    let mut offset = 0;
+    for word in text.split(|&(_, code)| code == SPACE) {
+        let glyphs = word
+            .iter()
+            .map(|&character| glyph(character))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut candidate = line.clone();
+        // The space a line was wrapped at is dropped rather than starting the next line.
+        let wrapped = line.is_empty() && lines.len() > first;
+        if offset > 0 && !wrapped {
+            composer.push(&mut candidate, &glyph(text[offset - 1])?);
+        }
+        offset += word.len() + 1;
+        glyphs
+            .iter()
+            .for_each(|glyph| composer.push(&mut candidate, glyph));
+        if candidate.width <= limit || candidate.characters == 0 {
+            line = candidate;
+            continue;
+        }
+
+        // The word does not fit: move it to a line of its own, breaking it up further
+        // if asked to and it is still too wide.
+        if !line.is_empty() {
+            lines.push(line);
+            line = Line::new(height);
+        }
+        for glyph in &glyphs {
+            let mut candidate = line.clone();
+            composer.push(&mut candidate, glyph);
+            if options.break_words && !line.is_empty() && candidate.width > limit {
+                lines.push(line);
+                line = Line::new(height);
+                composer.push(&mut line, glyph);
+            } else {
+                line = candidate;
+            }
+        }
+    }
+    lines.push(line);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+    use crate::figfont::{FigFont, REQUIRED_CHARACTERS};
+    use crate::font;
+    use crate::search::SearchPath;
+
+    fn standard() -> Renderer {
+        Renderer::new(font::load(None, &SearchPath::new(&[])).unwrap())
+    }
+
+    #[test]
+    fn smushes_with_the_font_layout_by_default() {
+        let banner = standard().render("Hi").unwrap();
+        assert_eq!(
+            banner.rows,
+            [
+                " _   _ _ ",
+                "| | | (_)",
+                "| |_| | |",
+                "|  _  | |",
+                "|_| |_|_|",
+                "         ",
+            ]
+        );
+    }
+
+    #[test]
+    fn layout_overrides_change_the_width() {
+        let smushed = standard().render("Hello").unwrap().width();
+        let fitted = standard()
+            .horizontal_layout(Some(LayoutOverride::Fitting))
+            .render("Hello")
+            .unwrap()
+            .width();
+        let full = standard()
+            .horizontal_layout(Some(LayoutOverride::Full))
+            .render("Hello")
+            .unwrap()
+            .width();
+        assert!(smushed < fitted && fitted < full);
+    }
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let banner = standard().width(Some(30)).render("Hello World").unwrap();
+        assert!(banner.width() <= 30);
+        assert!(banner.height() > 6);
+        let banner = standard()
+            .width(Some(30))
+            .vertical_layout(Some(LayoutOverride::Full))
+            .render("Hello World")
+            .unwrap();
+        assert_eq!(banner.height(), 12);
+    }
+
+    #[test]
+    fn wraps_one_column_short_of_the_width() {
+        let line = standard().render("Hi Hi").unwrap();
+        assert_eq!((line.width(), line.height()), (19, 6));
+        let fits = standard().width(Some(20)).render("Hi Hi").unwrap();
+        assert_eq!(fits.height(), 6);
+        let wrapped = standard().width(Some(19)).render("Hi Hi").unwrap();
+        assert!(wrapped.height() > 6);
+    }
+
+    #[test]
+    fn keeps_overlong_words_unless_breaking_them() {
+        let kept = standard().width(Some(10)).render("Hello").unwrap();
+        assert_eq!(kept.height(), 6);
+        assert!(kept.width() > 10);
+        let broken = standard()
+            .width(Some(10))
+            .break_words(true)
+            .render("Hello")
+            .unwrap();
+        assert!(broken.width() <= 10);
+    }
+
+    #[test]
+    fn justifies_within_the_width() {
+        let left = standard().render("Hi").unwrap();
+        let center = standard()
+            .justification(Justification::Center)
+            .width(Some(30))
+            .render("Hi")
+            .unwrap();
+        let right = standard()
+            .justification(Justification::Right)
+            .width(Some(30))
+            .render("Hi")
+            .unwrap();
+        assert_eq!(center.rows[1], format!("          {}", left.rows[1]));
+        assert_eq!(right.rows[1], format!("{}{}", " ".repeat(20), left.rows[1]));
+    }
+
+    #[test]
+    fn right_to_left_reverses_and_right_justifies() {
+        // Like figlet, the first FIGcharacter keeps its leading blank column right-to-left.
+        let banner = standard()
+            .direction(Direction::RightToLeft)
+            .width(Some(11))
+            .render("iH")
+            .unwrap();
+        let expected: Vec<String> = standard()
+            .render("Hi")
+            .unwrap()
+            .rows
+            .iter()
+            .map(|row| format!(" {}", row))
+            .collect();
+        assert_eq!(banner.rows, expected);
+        let banner = standard()
+            .direction(Direction::RightToLeft)
+            .render("iH")
+            .unwrap();
+        assert_eq!(banner.width(), DEFAULT_WIDTH - 1);
+    }
+
+    #[test]
+    fn newlines_start_fig_lines() {
+        let banner = standard()
+            .vertical_layout(Some(LayoutOverride::Full))
+            .render("a\nb")
+            .unwrap();
+        assert_eq!(banner.height(), 12);
+    }
+
+    #[test]
+    fn rejects_characters_missing_from_the_font() {
+        match standard().render("ok €") {
+            Err(FigctlError::UnsupportedCharacter { code, font }) => {
+                assert_eq!(code, '€' as i64);
+                assert_eq!(font, "standard");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn places_every_character() {
+        let columns = |banner: &Banner| -> Vec<(usize, Range<usize>)> {
+            banner
+                .placements
+                .iter()
+                .map(|placement| (placement.index, placement.columns.clone()))
+                .collect()
+        };
+        let full = standard()
+            .horizontal_layout(Some(LayoutOverride::Full))
+            .render("a b")
+            .unwrap();
+        assert_eq!(full.rows[1], "   __ _    | |__  ");
+        assert_eq!(columns(&full), [(0, 0..8), (1, 8..10), (2, 10..18)]);
+        let smushed = standard().render("ab").unwrap();
+        assert_eq!(columns(&smushed), [(0, 0..7), (1, 5..13)]);
+        assert!(smushed
+            .placements
+            .iter()
+            .all(|placement| placement.rows == (0..6)));
+        let right_to_left = standard()
+            .direction(Direction::RightToLeft)
+            .width(Some(12))
+            .render("ab")
+            .unwrap();
+        assert_eq!(columns(&right_to_left), [(0, 6..14), (1, 0..8)]);
+        let stacked = standard()
+            .vertical_layout(Some(LayoutOverride::Full))
+            .justification(Justification::Center)
+            .width(Some(20))
+            .render("a\nb")
+            .unwrap();
+        assert_eq!(stacked.placements[1].index, 2);
+        assert_eq!(stacked.placements[1].rows, 6..12);
+        assert_eq!(stacked.placements[1].columns, 6..13);
+    }
+
+    #[test]
+    fn applies_the_missing_character_policy() {
+        assert_eq!(
+            standard()
+                .missing(Missing::Skip)
+                .render("a€b")
+                .unwrap()
+                .rows,
+            standard().render("ab").unwrap().rows
+        );
+        assert_eq!(
+            standard()
+                .missing(Missing::Replace('?'))
+                .render("a€b")
+                .unwrap()
+                .rows,
+            standard().render("a?b").unwrap().rows
+        );
+        match standard().missing(Missing::Replace('€')).render("a€b") {
+            Err(FigctlError::UnsupportedCharacter { code, .. }) => assert_eq!(code, '€' as i64),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn draws_missing_characters_from_fallback_fonts() {
+        let dir = [std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts")];
+        let blocks = font::load(Some("blocks"), &SearchPath::new(&dir)).unwrap();
+        let banner = standard().fallback(blocks).render("全").unwrap();
+        // Both fonts have their baseline on their second last row.
+        assert_eq!(banner.height(), 6);
+        assert_eq!(banner.rows[3].trim_end(), "全");
+        assert_eq!(banner.rows[4].trim_end(), "▀▀");
+        assert!(banner.rows[..3].iter().all(|row| row.trim().is_empty()));
+    }
+
+    #[test]
+    fn keeps_the_hardblanks_of_fallback_fonts_apart() {
+        // A font with `#` as hardblank that draws U+263A with the standard font's `$`.
+        let source = format!(
+            "flf2a# 1 1 3 0 0\n{}0x263A\n$#$@\n",
+            "#@\n".repeat(REQUIRED_CHARACTERS)
+        );
+        let fallback = Font {
+            name: "dollars".to_string(),
+            path: None,
+            figfont: FigFont::parse(&source).unwrap(),
+        };
+        let banner = standard().fallback(fallback).render("\u{263A}$").unwrap();
+        assert_eq!(&banner.rows[4][..3], "$ $");
+    }
+
+    #[test]
+    fn renders_tabs_as_spaces() {
+        assert_eq!(
+            standard().render("a\tb").unwrap().rows,
+            standard().render("a b").unwrap().rows
+        );
+    }
+
+    #[test]
+    fn normalizes_messages() {
+        let composed = standard().render("Ä").unwrap();
+        assert_eq!(standard().render("A\u{308}").unwrap().rows, composed.rows);
+        assert_eq!(
+            standard().strip_marks(true).render("Café").unwrap().rows,
+            standard().render("Cafe").unwrap().rows
+        );
+        assert_eq!(
+            normalize(&[0x423021, 'e' as i64, 0x301], false),
+            [0x423021, 0xe9]
+        );
+        assert_eq!(normalize(&[0xe9, -1], true), ['e' as i64, -1]);
+    }
+
+    #[test]
+    fn parses_missing_character_policies() {
+        assert_eq!("error".parse(), Ok(Missing::Error));
+        assert_eq!("skip".parse(), Ok(Missing::Skip));
+        assert_eq!("replace:*".parse(), Ok(Missing::Replace('*')));
+        assert!("replace:".parse::<Missing>().is_err());
+        assert!("replace:ab".parse::<Missing>().is_err());
+        assert!("ignore".parse::<Missing>().is_err());
+    }
+
+    #[test]
+    fn parses_directions() {
+        assert_eq!("rtl".parse(), Ok(Direction::RightToLeft));
+        assert_eq!("ltr".parse(), Ok(Direction::LeftToRight));
+        assert_eq!("auto".parse(), Ok(Direction::Auto));
+        assert!("up".parse::<Direction>().is_err());
+    }
+}
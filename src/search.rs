@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions of font files: FIGlet fonts and TOIlet fonts, either of them maybe gzipped.
+const EXTENSIONS: [&str; 4] = ["flf", "tlf", "flf.gz", "tlf.gz"];
+
+/// Ordered list of directories that fonts given by name are looked up in.
+///
+/// Directories are searched in this order:
+///
+/// 1. every `--font-dir`, in the order given on the command line
+/// 2. `$FIGLET_FONTDIR` (may hold several directories, separated like `$PATH`)
+/// 3. `$XDG_DATA_HOME/figctl/fonts`, falling back to `~/.local/share/figctl/fonts`
+/// 4. `/usr/share/figlet`
+/// 5. the current directory
+#[derive(Debug, Clone)]
+pub struct SearchPath {
+    dirs: Vec<PathBuf>,
+}
+
+impl SearchPath {
+    pub fn new(font_dirs: &[PathBuf]) -> SearchPath {
+        let mut dirs = font_dirs.to_vec();
+        if let Some(fontdir) = env::var_os("FIGLET_FONTDIR") {
+            dirs.extend(env::split_paths(&fontdir).filter(|dir| !dir.as_os_str().is_empty()));
+        }
+        if let Some(data_home) = data_home() {
+            dirs.push(data_home.join("figctl").join("fonts"));
+        }
+        dirs.push(PathBuf::from("/usr/share/figlet"));
+        dirs.push(PathBuf::from("."));
+        SearchPath { dirs }
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Returns the first `<name>.flf` or `<name>.tlf` found on the search path, preferring
+    /// the FIGlet font when a directory has both and uncompressed fonts over gzipped ones.
+    /// A name that already carries one of the extensions is only looked up as is.
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        let file_names: Vec<String> = if font_name(Path::new(name)).is_some() {
+            vec![name.to_string()]
+        } else {
+            EXTENSIONS
+                .iter()
+                .map(|extension| format!("{}.{}", name, extension))
+                .collect()
+        };
+        self.dirs
+            .iter()
+            .flat_map(|dir| file_names.iter().map(move |file_name| dir.join(file_name)))
+            .find(|path| path.is_file())
+    }
+
+    /// Returns the first control file `<name>.flc` found on the search path, or `name` itself
+    /// when it already ends in `.flc`.
+    pub fn find_control(&self, name: &str) -> Option<PathBuf> {
+        let file_name = if name.ends_with(".flc") {
+            name.to_string()
+        } else {
+            format!("{}.flc", name)
+        };
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+    }
+
+    /// Returns every font file on the search path, skipping fonts that are shadowed
+    /// by a font of the same name in an earlier directory.
+    pub fn fonts(&self) -> Vec<PathBuf> {
+        let mut names = HashSet::new();
+        let mut fonts = Vec::new();
+        for dir in &self.dirs {
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && font_name(path).is_some())
+                .collect();
+            paths.sort();
+            for path in paths {
+                if names.insert(font_name(&path).map(str::to_string)) {
+                    fonts.push(path);
+                }
+            }
+        }
+        fonts
+    }
+}
+
+/// Name of the font in `path`: its file name without the font extension, or `None` when
+/// it does not have one.
+pub fn font_name(path: &Path) -> Option<&str> {
+    let file_name = path.file_name()?.to_str()?;
+    EXTENSIONS
+        .iter()
+        .find_map(|extension| file_name.strip_suffix(extension)?.strip_suffix('.'))
+}
+
+fn data_home() -> Option<PathBuf> {
+    match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if Path::new(&dir).is_absolute() => Some(PathBuf::from(dir)),
+        _ => env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")),
+    }
+}
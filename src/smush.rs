@@ -0,0 +1,168 @@
+use crate::layout::{
+    BIG_X, EQUAL, HARDBLANK, HIERARCHY, HORIZONTAL_LINE, PAIR, UNDERSCORE, VERTICAL_EQUAL,
+    VERTICAL_HIERARCHY, VERTICAL_LINE, VERTICAL_UNDERSCORE,
+};
+
+/// Classes of the hierarchy rule, from lowest to highest. When two sub-characters from
+/// different classes meet, the one from the higher class wins.
+const HIERARCHY_CLASSES: [&str; 6] = ["|", "/\\", "[]", "{}", "()", "<>"];
+
+/// Sub-characters an underscore gives way to.
+const UNDERSCORE_REPLACEMENTS: &str = "|/\\[]{}()<>";
+
+/// Smushes two non-blank sub-characters that meet horizontally into one.
+///
+/// `rules` holds the horizontal rule bits; without any, universal smushing applies and
+/// the right sub-character (the left one for right-to-left text) wins over anything but a
+/// hardblank. Returns `None` when no rule allows the two to be smushed.
+pub fn horizontal(
+    left: char,
+    right: char,
+    rules: i32,
+    hardblank: char,
+    right_to_left: bool,
+) -> Option<char> {
+    if rules == 0 {
+        return Some(if left == hardblank {
+            right
+        } else if right == hardblank || right_to_left {
+            left
+        } else {
+            right
+        });
+    }
+    if rules & HARDBLANK != 0 && left == hardblank && right == hardblank {
+        return Some(left);
+    }
+    if left == hardblank || right == hardblank {
+        return None;
+    }
+    if rules & EQUAL != 0 && left == right {
+        return Some(left);
+    }
+    if rules & UNDERSCORE != 0 {
+        if let Some(winner) = underscore(left, right) {
+            return Some(winner);
+        }
+    }
+    if rules & HIERARCHY != 0 {
+        if let Some(winner) = hierarchy(left, right) {
+            return Some(winner);
+        }
+    }
+    if rules & PAIR != 0
+        && matches!(
+            (left, right),
+            ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(')
+        )
+    {
+        return Some('|');
+    }
+    if rules & BIG_X != 0 {
+        match (left, right) {
+            ('/', '\\') => return Some('|'),
+            ('\\', '/') => return Some('Y'),
+            ('>', '<') => return Some('X'),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Smushes two non-blank sub-characters that meet vertically, `upper` above `lower`.
+///
+/// `rules` holds the vertical rule bits as found in the header; without any, universal
+/// smushing lets the lower sub-character win over anything but a hardblank. Vertical line
+/// supersmushing is left to the caller, as it lets lines overlap by more than one row.
+pub fn vertical(upper: char, lower: char, rules: i32, hardblank: char) -> Option<char> {
+    if rules == 0 {
+        return Some(if lower == hardblank { upper } else { lower });
+    }
+    if rules & VERTICAL_EQUAL != 0 && upper == lower {
+        return Some(upper);
+    }
+    if rules & VERTICAL_UNDERSCORE != 0 {
+        if let Some(winner) = underscore(upper, lower) {
+            return Some(winner);
+        }
+    }
+    if rules & VERTICAL_HIERARCHY != 0 {
+        if let Some(winner) = hierarchy(upper, lower) {
+            return Some(winner);
+        }
+    }
+    if rules & HORIZONTAL_LINE != 0 && matches!((upper, lower), ('-', '_') | ('_', '-')) {
+        return Some('=');
+    }
+    if rules & VERTICAL_LINE != 0 && upper == '|' && lower == '|' {
+        return Some('|');
+    }
+    None
+}
+
+fn underscore(a: char, b: char) -> Option<char> {
+    if a == '_' && UNDERSCORE_REPLACEMENTS.contains(b) {
+        Some(b)
+    } else if b == '_' && UNDERSCORE_REPLACEMENTS.contains(a) {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+fn hierarchy(a: char, b: char) -> Option<char> {
+    let class = |c| HIERARCHY_CLASSES.iter().position(|class| class.contains(c));
+    match (class(a)?, class(b)?) {
+        (a_class, b_class) if a_class < b_class => Some(b),
+        (a_class, b_class) if b_class < a_class => Some(a),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn horizontal_rules() {
+        assert_eq!(horizontal('a', 'a', EQUAL, '$', false), Some('a'));
+        assert_eq!(horizontal('a', 'b', EQUAL, '$', false), None);
+        assert_eq!(horizontal('_', '/', UNDERSCORE, '$', false), Some('/'));
+        assert_eq!(horizontal('|', '_', UNDERSCORE, '$', false), Some('|'));
+        assert_eq!(horizontal('|', '/', HIERARCHY, '$', false), Some('/'));
+        assert_eq!(horizontal('<', '[', HIERARCHY, '$', false), Some('<'));
+        assert_eq!(horizontal('/', '\\', HIERARCHY, '$', false), None);
+        assert_eq!(horizontal(']', '[', PAIR, '$', false), Some('|'));
+        assert_eq!(horizontal('(', ')', PAIR, '$', false), Some('|'));
+        assert_eq!(horizontal('/', '\\', BIG_X, '$', false), Some('|'));
+        assert_eq!(horizontal('\\', '/', BIG_X, '$', false), Some('Y'));
+        assert_eq!(horizontal('>', '<', BIG_X, '$', false), Some('X'));
+        assert_eq!(horizontal('<', '>', BIG_X, '$', false), None);
+        assert_eq!(horizontal('$', '$', HARDBLANK, '$', false), Some('$'));
+        assert_eq!(horizontal('$', '$', EQUAL, '$', false), None);
+    }
+
+    #[test]
+    fn universal_horizontal_smushing() {
+        assert_eq!(horizontal('a', 'b', 0, '$', false), Some('b'));
+        assert_eq!(horizontal('a', 'b', 0, '$', true), Some('a'));
+        assert_eq!(horizontal('a', '$', 0, '$', false), Some('a'));
+        assert_eq!(horizontal('$', 'b', 0, '$', true), Some('b'));
+    }
+
+    #[test]
+    fn vertical_rules() {
+        assert_eq!(vertical('a', 'a', VERTICAL_EQUAL, '$'), Some('a'));
+        assert_eq!(vertical('_', '|', VERTICAL_UNDERSCORE, '$'), Some('|'));
+        assert_eq!(vertical('{', '/', VERTICAL_HIERARCHY, '$'), Some('{'));
+        assert_eq!(vertical('-', '_', HORIZONTAL_LINE, '$'), Some('='));
+        assert_eq!(vertical('_', '-', HORIZONTAL_LINE, '$'), Some('='));
+        assert_eq!(vertical('|', '|', VERTICAL_LINE, '$'), Some('|'));
+        assert_eq!(
+            vertical('a', 'b', VERTICAL_EQUAL | VERTICAL_LINE, '$'),
+            None
+        );
+        assert_eq!(vertical('a', 'b', 0, '$'), Some('b'));
+        assert_eq!(vertical('a', '$', 0, '$'), Some('a'));
+    }
+}
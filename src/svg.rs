@@ -0,0 +1,271 @@
+//! SVG output: the banner as a grid of fixed-size cells in a scalable image.
+//!
+//! In text mode every row is a `<text>` element, with one `<tspan>` for each run of
+//! sub-characters of the same colour that is stretched to exactly the width of its cells,
+//! so the banner lines up whatever monospaced font draws it. In cell mode every filled cell
+//! is a `<rect>` instead, which suits fonts drawn with block characters; half blocks fill
+//! half a cell.
+
+use std::io::{self, Write};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::banner::Banner;
+use crate::color::Rgb;
+use crate::html::escape;
+
+/// How sub-characters are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SvgMode {
+    /// As text in a monospaced font.
+    #[default]
+    Text,
+    /// As a rectangle filling each cell that is not blank.
+    Cells,
+}
+
+/// Options of the SVG output format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub mode: SvgMode,
+    /// Font family of text mode, `monospace` when `None`.
+    pub font_family: Option<String>,
+    /// Colour of sub-characters the banner does not colour, black when `None`.
+    pub foreground: Option<Rgb>,
+    /// Colour filling the whole image, transparent when `None`.
+    pub background: Option<Rgb>,
+    /// Margin around the banner, in pixels.
+    pub padding: usize,
+    /// Size of a cell one column wide, in pixels.
+    pub cell_width: usize,
+    pub cell_height: usize,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            mode: SvgMode::Text,
+            font_family: None,
+            foreground: None,
+            background: None,
+            padding: 0,
+            cell_width: 10,
+            cell_height: 20,
+        }
+    }
+}
+
+/// Share of the cell height between the top of a cell and the baseline of its text.
+const BASELINE: f64 = 0.8;
+
+pub(crate) fn write(banner: &Banner, options: &SvgOptions, out: &mut impl Write) -> io::Result<()> {
+    let width = banner.width() * options.cell_width + 2 * options.padding;
+    let height = banner.height() * options.cell_height + 2 * options.padding;
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )?;
+    if let Some(background) = options.background {
+        writeln!(
+            out,
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            width, height, background
+        )?;
+    }
+    let foreground = options.foreground.unwrap_or(Rgb::new(0, 0, 0));
+    match options.mode {
+        SvgMode::Text => {
+            let font_family = options.font_family.as_deref().unwrap_or("monospace");
+            writeln!(
+                out,
+                "<g font-family=\"{}\" font-size=\"{}\" fill=\"{}\" xml:space=\"preserve\">",
+                escape(font_family),
+                options.cell_height,
+                foreground
+            )?;
+            for row in 0..banner.height() {
+                write_text_row(banner, row, options, out)?;
+            }
+        }
+        SvgMode::Cells => {
+            writeln!(out, "<g fill=\"{}\">", foreground)?;
+            for row in 0..banner.height() {
+                write_cell_row(banner, row, options, out)?;
+            }
+        }
+    }
+    writeln!(out, "</g>")?;
+    writeln!(out, "</svg>")
+}
+
+/// A run of sub-characters of one colour without blanks, starting at `column`.
+struct Run {
+    column: usize,
+    width: usize,
+    text: String,
+    color: Option<Rgb>,
+}
+
+fn runs(banner: &Banner, row: usize) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for (column, c) in banner.cells(row) {
+        let width = c.width().unwrap_or(0);
+        if c == ' ' || width == 0 {
+            continue;
+        }
+        let color = banner.color(row, column);
+        match runs.last_mut() {
+            Some(run) if run.column + run.width == column && run.color == color => {
+                run.width += width;
+                run.text.push(c);
+            }
+            _ => runs.push(Run {
+                column,
+                width,
+                text: c.to_string(),
+                color,
+            }),
+        }
+    }
+    runs
+}
+
+fn write_text_row(
+    banner: &Banner,
+    row: usize,
+    options: &SvgOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let runs = runs(banner, row);
+    if runs.is_empty() {
+        return Ok(());
+    }
+    let top = options.padding + row * options.cell_height;
+    let baseline = top as f64 + options.cell_height as f64 * BASELINE;
+    write!(out, "<text y=\"{}\">", baseline)?;
+    for run in runs {
+        write!(
+            out,
+            "<tspan x=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\"",
+            options.padding + run.column * options.cell_width,
+            run.width * options.cell_width
+        )?;
+        if let Some(color) = run.color {
+            write!(out, " fill=\"{}\"", color)?;
+        }
+        write!(out, ">{}</tspan>", escape(&run.text))?;
+    }
+    writeln!(out, "</text>")
+}
+
+fn write_cell_row(
+    banner: &Banner,
+    row: usize,
+    options: &SvgOptions,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let (cell_width, cell_height) = (options.cell_width, options.cell_height);
+    for (column, c) in banner.cells(row) {
+        let width = c.width().unwrap_or(0);
+        if c == ' ' || width == 0 {
+            continue;
+        }
+        let (x, y) = (
+            options.padding + column * cell_width,
+            options.padding + row * cell_height,
+        );
+        let (half_width, half_height) = (cell_width / 2, cell_height / 2);
+        let (x, y, width, height) = match c {
+            '▀' => (x, y, cell_width, half_height),
+            '▄' => (x, y + half_height, cell_width, cell_height - half_height),
+            '▌' => (x, y, half_width, cell_height),
+            '▐' => (x + half_width, y, cell_width - half_width, cell_height),
+            _ => (x, y, width * cell_width, cell_height),
+        };
+        write!(
+            out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+            x, y, width, height
+        )?;
+        if let Some(color) = banner.color(row, column) {
+            write!(out, " fill=\"{}\"", color)?;
+        }
+        writeln!(out, "/>")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::banner::OutputFormat;
+
+    fn svg(rows: &[&str], colors: Vec<Vec<Option<Rgb>>>, options: SvgOptions) -> String {
+        let banner = Banner {
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            text: String::new(),
+            placements: Vec::new(),
+            colors,
+            format: OutputFormat::Svg(options),
+        };
+        let mut out = Vec::new();
+        banner.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn writes_rows_as_stretched_text() {
+        let red = Some(Rgb::new(255, 0, 0));
+        let svg = svg(
+            &["a<b  c", "", "全d"],
+            vec![vec![None, red, red, None, None, None]],
+            SvgOptions::default(),
+        );
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"60\" height=\"60\" \
+             viewBox=\"0 0 60 60\">\n\
+             <g font-family=\"monospace\" font-size=\"20\" fill=\"#000000\" \
+             xml:space=\"preserve\">\n\
+             <text y=\"16\">\
+             <tspan x=\"0\" textLength=\"10\" lengthAdjust=\"spacingAndGlyphs\">a</tspan>\
+             <tspan x=\"10\" textLength=\"20\" lengthAdjust=\"spacingAndGlyphs\" \
+             fill=\"#ff0000\">&lt;b</tspan>\
+             <tspan x=\"50\" textLength=\"10\" lengthAdjust=\"spacingAndGlyphs\">c</tspan>\
+             </text>\n\
+             <text y=\"56\">\
+             <tspan x=\"0\" textLength=\"30\" lengthAdjust=\"spacingAndGlyphs\">全d</tspan>\
+             </text>\n\
+             </g>\n\
+             </svg>\n"
+        );
+    }
+
+    #[test]
+    fn fills_cells_with_rectangles() {
+        let options = SvgOptions {
+            mode: SvgMode::Cells,
+            foreground: Some(Rgb::new(0, 0, 255)),
+            background: Some(Rgb::new(255, 255, 255)),
+            padding: 2,
+            cell_width: 4,
+            cell_height: 8,
+            ..SvgOptions::default()
+        };
+        let svg = svg(&["█ ▀", "▐"], Vec::new(), options);
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"16\" height=\"20\" \
+             viewBox=\"0 0 16 20\">\n\
+             <rect width=\"16\" height=\"20\" fill=\"#ffffff\"/>\n\
+             <g fill=\"#0000ff\">\n\
+             <rect x=\"2\" y=\"2\" width=\"4\" height=\"8\"/>\n\
+             <rect x=\"10\" y=\"2\" width=\"4\" height=\"4\"/>\n\
+             <rect x=\"4\" y=\"10\" width=\"2\" height=\"8\"/>\n\
+             </g>\n\
+             </svg>\n"
+        );
+    }
+}
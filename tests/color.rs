@@ -0,0 +1,127 @@
+//! Tests for coloured output with `--color`.
+
+mod common;
+
+use std::process::Output;
+
+use common::{command, stdout};
+
+/// Runs figctl in a terminal described by `colorterm` and `term`, with standard output
+/// redirected as it always is in tests.
+fn figctl(args: &[&str], colorterm: Option<&str>, term: Option<&str>) -> Output {
+    let mut command = command(args);
+    command.env_remove("NO_COLOR");
+    for (name, value) in [("COLORTERM", colorterm), ("TERM", term)] {
+        match value {
+            Some(value) => command.env(name, value),
+            None => command.env_remove(name),
+        };
+    }
+    command.output().expect("failed to run figctl")
+}
+
+fn render(args: &[&str], colorterm: Option<&str>, term: Option<&str>) -> String {
+    String::from_utf8(stdout(args, figctl(args, colorterm, term))).unwrap()
+}
+
+/// Removes the colour escape sequences from `output`.
+fn strip(output: &str) -> String {
+    let mut plain = String::new();
+    let mut rest = output;
+    while let Some(start) = rest.find('\x1b') {
+        plain.push_str(&rest[..start]);
+        let end = rest[start..].find('m').unwrap();
+        rest = &rest[start + end + 1..];
+    }
+    plain + rest
+}
+
+#[test]
+fn writes_no_colours_unless_on_a_terminal() {
+    let plain = render(&["Hi"], None, None);
+    assert_eq!(
+        render(&["--color", "red", "Hi"], Some("truecolor"), None),
+        plain
+    );
+    assert_eq!(
+        render(
+            &["--color", "always", "--color", "red", "--color", "never", "Hi"],
+            None,
+            None
+        ),
+        plain
+    );
+}
+
+#[test]
+fn writes_escape_sequences_the_terminal_supports() {
+    let args = ["--color=always", "--color=red", "Hi"];
+    let truecolor = render(&args, Some("truecolor"), Some("xterm"));
+    assert!(
+        truecolor.starts_with(" \x1b[38;2;205;0;0m_"),
+        "{:?}",
+        truecolor
+    );
+    let extended = render(&args, None, Some("xterm-256color"));
+    assert!(extended.starts_with(" \x1b[38;5;160m_"), "{:?}", extended);
+    let basic = render(&args, None, Some("dumb"));
+    assert!(basic.starts_with(" \x1b[31m_"), "{:?}", basic);
+    assert!(basic.lines().take(5).all(|line| line.ends_with("\x1b[0m")));
+    assert_eq!(strip(&basic), render(&["Hi"], None, None));
+}
+
+#[test]
+fn colours_every_character_in_turn_with_a_palette() {
+    let output = render(
+        &[
+            "--color=always",
+            "--color=palette:#ff0000:#0000ff",
+            "-W",
+            "I I",
+        ],
+        Some("truecolor"),
+        None,
+    );
+    let red = "\x1b[38;2;255;0;0m";
+    let blue = "\x1b[38;2;0;0;255m";
+    let first = format!("  {}___     {}___ \x1b[0m\n", red, blue);
+    assert!(output.starts_with(&first), "{:?}", output);
+}
+
+#[test]
+fn gradients_blend_across_the_banner() {
+    let output = render(
+        &[
+            "--color=always",
+            "--color=gradient:#000000:#ffffff",
+            "-W",
+            "III",
+        ],
+        Some("truecolor"),
+        None,
+    );
+    let first = output.lines().nth(1).unwrap();
+    assert!(first.starts_with(" \x1b[38;2;15;15;15m|"), "{:?}", first);
+    assert!(
+        first.ends_with("\x1b[38;2;255;255;255m|\x1b[0m"),
+        "{:?}",
+        first
+    );
+    assert_eq!(
+        strip(&render(
+            &["--color=always", "--color=rainbow", "Hi"],
+            None,
+            None
+        )),
+        render(&["Hi"], None, None)
+    );
+}
+
+#[test]
+fn rejects_unknown_colours() {
+    let output = figctl(&["--color", "chartreuse", "x"], None, None);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("unknown colour 'chartreuse'"));
+}
@@ -0,0 +1,46 @@
+//! Tests for `--format comment`.
+
+mod common;
+
+use common::{figctl, render};
+
+#[test]
+fn comments_out_every_row() {
+    assert_eq!(
+        render(&["--format", "comment", "--lang", "rust", "Hi"]),
+        "//  _   _ _\n\
+         // | | | (_)\n\
+         // | |_| | |\n\
+         // |  _  | |\n\
+         // |_| |_|_|\n\
+         //\n"
+    );
+    let lua = render(&["--format", "comment", "--lang", "lua", "Hi"]);
+    assert!(lua.starts_with("--  _   _ _\n-- | | | (_)\n"));
+    let html = render(&["--format", "comment", "--lang", "html", "Hi"]);
+    assert!(html.starts_with("<!--\n _   _ _\n"));
+    assert!(html.ends_with("|_| |_|_|\n\n-->\n"));
+}
+
+#[test]
+fn boxes_the_banner() {
+    let shell = render(&["--format", "comment", "--lang", "sh", "--box", "Hi"]);
+    let lines: Vec<&str> = shell.lines().collect();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], "#".repeat(13));
+    assert_eq!(lines[2], "# | | | (_) #");
+    assert_eq!(lines[6], "#           #");
+    assert_eq!(lines[7], lines[0]);
+    let c = render(&["--format", "comment", "--lang", "c", "--box", "Hi"]);
+    assert!(c.starts_with("/*************\n *  _   _ _  *\n"));
+    assert!(c.ends_with(" *           *\n ************/\n"));
+}
+
+#[test]
+fn requires_a_known_language() {
+    let output = figctl(&["--format", "comment", "Hi"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = figctl(&["--format", "comment", "--lang", "cobol", "Hi"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown language 'cobol'"));
+}
@@ -0,0 +1,31 @@
+//! Helpers shared by the integration tests, each of which uses only some of them.
+#![allow(dead_code)]
+
+use std::process::{Command, Output};
+
+/// figctl with `args`, run from the crate root so test fonts can be given by relative path.
+pub fn command(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_figctl"));
+    command.current_dir(env!("CARGO_MANIFEST_DIR")).args(args);
+    command
+}
+
+pub fn figctl(args: &[&str]) -> Output {
+    command(args).output().expect("failed to run figctl")
+}
+
+/// Standard output of a run of figctl with `args`, which has to have succeeded.
+pub fn stdout(args: &[&str], output: Output) -> Vec<u8> {
+    assert!(
+        output.status.success(),
+        "figctl {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output.stdout
+}
+
+/// Renders `args` as text, failing the test if figctl does.
+pub fn render(args: &[&str]) -> String {
+    String::from_utf8(stdout(args, figctl(args))).expect("figctl wrote invalid UTF-8")
+}
@@ -0,0 +1,51 @@
+//! Tests for fonts stored gzipped or zipped.
+//!
+//! Every font in `fonts/compressed/` is `fonts/rules.flf` compressed in some way, so each
+//! has to render exactly like it.
+
+mod common;
+
+use common::figctl;
+
+const DIR: &str = "tests/fonts/compressed";
+
+fn render(font: &str) -> String {
+    common::render(&["-d", DIR, "-f", font, "ab"])
+}
+
+#[test]
+fn gzipped_fonts() {
+    let expected = render("tests/fonts/rules.flf");
+    assert_eq!(render("gzipped"), expected);
+    assert_eq!(render("gzipped.flf.gz"), expected);
+    assert_eq!(render("tests/fonts/compressed/gzipped.flf.gz"), expected);
+}
+
+#[test]
+fn compression_is_detected_by_content() {
+    let expected = render("tests/fonts/rules.flf");
+    assert_eq!(render("disguised"), expected);
+    assert_eq!(render("zipped"), expected);
+}
+
+#[test]
+fn lists_compressed_fonts_by_name() {
+    let output = figctl(&["list-fonts", "-d", DIR]);
+    let listing = String::from_utf8(output.stdout).unwrap();
+    assert!(listing.contains("gzipped (tests/fonts/compressed/gzipped.flf.gz)"));
+    assert!(listing.contains("zipped (tests/fonts/compressed/zipped.flf)"));
+}
+
+#[test]
+fn rejects_corrupt_archives() {
+    let output = figctl(&["-f", "tests/fonts/compressed/corrupt.flf", "ab"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with(
+        "figctl: malformed font tests/fonts/compressed/corrupt.flf, line 1, column 1: \
+         corrupt archive: "
+    ));
+    let output = figctl(&["-f", "tests/fonts/compressed/truncated.flf", "ab"]);
+    assert_eq!(output.status.code(), Some(4));
+    let output = figctl(&["-f", "tests/fonts/compressed/missing.flf.gz", "ab"]);
+    assert_eq!(output.status.code(), Some(6));
+}
@@ -0,0 +1,216 @@
+//! Conformance tests for the FIGfont 2.2 parser.
+//!
+//! The sample fonts in `fonts/conformance/` draw every character as itself next to a
+//! hardblank on the top row and twice on the bottom row, so parsed rows are easy to
+//! predict. The broken ones each carry a single defect and must be rejected with the
+//! line and column it is at.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::{figctl, render};
+use figctl::figfont::{FigFont, ParseError, REQUIRED_CHARACTERS};
+
+fn sample(name: &str) -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fonts/conformance")
+        .join(format!("{}.flf", name))
+        .display()
+        .to_string()
+}
+
+fn parse(name: &str) -> Result<FigFont, ParseError> {
+    let bytes = fs::read(sample(name)).unwrap();
+    let source = String::from_utf8(bytes)
+        .unwrap_or_else(|err| err.into_bytes().into_iter().map(char::from).collect());
+    FigFont::parse(&source)
+}
+
+fn rows(font: &FigFont, code: i64) -> Vec<&str> {
+    font.characters[&code].iter().map(String::as_str).collect()
+}
+
+fn error(name: &str) -> (usize, usize, String) {
+    let err = parse(name).expect_err("font should be rejected");
+    (err.line, err.column, err.message)
+}
+
+#[test]
+fn header() {
+    let font = parse("tagged").unwrap();
+    let header = &font.header;
+    assert_eq!(header.hardblank, '$');
+    assert_eq!(header.height, 2);
+    assert_eq!(header.baseline, 1);
+    assert_eq!(header.max_length, 4);
+    assert_eq!(header.old_layout, 0);
+    assert_eq!(header.comment_lines, 2);
+    assert_eq!(header.print_direction, Some(0));
+    assert_eq!(header.full_layout, Some(64));
+    assert_eq!(header.codetag_count, Some(5));
+    assert!(font.comments.starts_with("Conformance sample"));
+    assert!(font.comments.ends_with("at the bottom."));
+}
+
+#[test]
+fn required_characters() {
+    let font = parse("tagged").unwrap();
+    assert_eq!(rows(&font, ' ' as i64), [" $", "  "]);
+    assert_eq!(rows(&font, 'A' as i64), ["A$", "AA"]);
+    assert_eq!(rows(&font, '~' as i64), ["~$", "~~"]);
+    // The endmark is whatever character ends the row, '#' for '@'.
+    assert_eq!(rows(&font, '@' as i64), ["@$", "@@"]);
+    for (code, character) in [196, 214, 220, 228, 246, 252, 223]
+        .into_iter()
+        .zip("ÄÖÜäöüß".chars())
+    {
+        assert_eq!(
+            rows(&font, code),
+            [format!("{}$", character), character.to_string().repeat(2)]
+        );
+    }
+}
+
+#[test]
+fn code_tagged_characters() {
+    let font = parse("tagged").unwrap();
+    assert_eq!(font.characters.len(), REQUIRED_CHARACTERS + 5);
+    assert_eq!(rows(&font, 0x100), ["Ā$", "ĀĀ"]);
+    assert_eq!(rows(&font, 0x102), ["Ă$", "ĂĂ"]);
+    assert_eq!(rows(&font, 0x104), ["Ą$", "ĄĄ"]);
+    assert_eq!(rows(&font, 0x105), ["ą$", "ąą"]);
+    assert_eq!(rows(&font, -2), ["n", "n"]);
+    assert_eq!(font.character('Ă').unwrap(), ["Ă$", "ĂĂ"]);
+}
+
+#[test]
+fn latin1_and_crlf() {
+    let font = parse("latin1").unwrap();
+    assert_eq!(font.header.hardblank, '#');
+    assert_eq!(font.header.print_direction, Some(1));
+    assert_eq!(font.header.full_layout, None);
+    assert!(font.comments.contains("François"));
+    assert_eq!(rows(&font, 'x' as i64), ["x#", "xx"]);
+    assert_eq!(rows(&font, '|' as i64), ["|#", "||"]);
+}
+
+#[test]
+fn bundled_fonts() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+        let font =
+            FigFont::parse(&source).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        assert!(font.characters.len() >= REQUIRED_CHARACTERS);
+        for rows in font.characters.values() {
+            assert_eq!(rows.len(), font.header.height, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn rejects_bad_signature() {
+    assert_eq!(
+        error("bad_signature"),
+        (1, 1, "missing flf2a or tlf2a signature".to_string())
+    );
+}
+
+#[test]
+fn rejects_bad_header() {
+    assert_eq!(
+        error("bad_header"),
+        (
+            1,
+            16,
+            "invalid comment line count 'two' in header".to_string()
+        )
+    );
+}
+
+#[test]
+fn rejects_implausible_headers() {
+    assert_eq!(
+        error("huge_height"),
+        (
+            1,
+            8,
+            "height must be between 1 and 1000, got 2000000000".to_string()
+        )
+    );
+    assert_eq!(
+        error("deep_baseline"),
+        (1, 10, "baseline must be between 0 and 2, got 3".to_string())
+    );
+    // Counts are not trusted to size anything before the lines they promise are read.
+    assert_eq!(
+        error("huge_comments"),
+        (1, 26, "font ends inside its comment block".to_string())
+    );
+}
+
+#[test]
+fn rejects_truncated_fonts() {
+    assert_eq!(
+        error("truncated"),
+        (
+            104,
+            4,
+            "font ends inside a character, after 1 of its 2 rows".to_string()
+        )
+    );
+    assert_eq!(
+        error("missing_deutsch"),
+        (
+            193,
+            5,
+            "font ends after 95 of the 102 required characters".to_string()
+        )
+    );
+}
+
+#[test]
+fn rejects_rows_without_endmark() {
+    assert_eq!(
+        error("no_endmark"),
+        (70, 1, "character row without an endmark".to_string())
+    );
+}
+
+#[test]
+fn rejects_bad_code_tags() {
+    assert_eq!(
+        error("bad_code"),
+        (208, 1, "invalid character code 'U+0100'".to_string())
+    );
+    assert_eq!(
+        error("reserved_code"),
+        (208, 1, "character code -1 is reserved".to_string())
+    );
+}
+
+#[test]
+fn renders_code_tagged_characters() {
+    assert_eq!(
+        render(&["-f", &sample("tagged"), "-W", "aĀĂĄą"]),
+        "a Ā Ă Ą ą \naaĀĀĂĂĄĄąą\n\n"
+    );
+}
+
+#[test]
+fn reports_errors_with_their_position() {
+    let font = sample("bad_header");
+    let output = figctl(&["-f", &font, "hi"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "figctl: malformed font {}, line 1, column 16: invalid comment line count 'two' \
+             in header\n",
+            font
+        )
+    );
+}
@@ -0,0 +1,97 @@
+//! Tests for figlet control files given with `--control`.
+
+mod common;
+
+use std::io::Write;
+use std::process::{Output, Stdio};
+
+use common::{command, render};
+
+fn figctl(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = command(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run figctl");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn maps_characters_the_font_lacks() {
+    assert_eq!(
+        render(&["--control", "tests/controls/accents.flc", "Café crème"]),
+        render(&["Cafe creme"])
+    );
+}
+
+#[test]
+fn finds_control_files_by_name() {
+    assert_eq!(
+        render(&["-d", "tests/controls", "-C", "accents", "àçé"]),
+        render(&["ace"])
+    );
+}
+
+#[test]
+fn applies_control_files_in_order() {
+    assert_eq!(
+        render(&[
+            "-d",
+            "tests/controls",
+            "-C",
+            "accents",
+            "-C",
+            "upper",
+            "déjà vu"
+        ]),
+        render(&["DEJA VU"])
+    );
+}
+
+#[test]
+fn reads_other_input_encodings() {
+    let output = figctl(
+        &["-d", "tests/controls", "-C", "latin1", "-C", "accents"],
+        b"caf\xe9",
+    );
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), render(&["cafe"]));
+}
+
+#[test]
+fn rejects_invalid_utf8_without_a_control_file() {
+    let output = figctl(&[], b"caf\xe9");
+    assert_eq!(output.status.code(), Some(6));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("figctl: input is not valid UTF-8"));
+}
+
+#[test]
+fn reports_malformed_control_files() {
+    let output = figctl(&["-C", "tests/controls/broken.flc", "x"], b"");
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "figctl: malformed control file tests/controls/broken.flc, line 3, column 3: \
+         translation range ends before it starts\n"
+    );
+}
+
+#[test]
+fn reports_corrupt_compressed_control_files() {
+    let output = figctl(&["-C", "tests/controls/truncated.flc.gz", "x"], b"");
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with(
+        "figctl: malformed control file tests/controls/truncated.flc.gz, line 1, column 1: \
+         corrupt archive: "
+    ));
+}
+
+#[test]
+fn reports_missing_control_files() {
+    let output = figctl(&["-C", "no-such-control", "x"], b"");
+    assert_eq!(output.status.code(), Some(6));
+}
@@ -0,0 +1,71 @@
+//! Tests for fallback chains given with `--font a,b,c`.
+//!
+//! `fonts/greek.flf` is four rows tall with its baseline on the second row, so its
+//! FIGcharacters reach one row further below the baseline than those of the standard font.
+
+mod common;
+
+use common::{figctl, render};
+
+#[test]
+fn draws_each_character_from_the_first_font_that_has_it() {
+    assert_eq!(
+        render(&["-d", "tests/fonts", "-f", "greek,standard", "Ω"]),
+        "/~\\\n| |\n| |\n~ ~\n\n"
+    );
+    assert_eq!(
+        render(&["-d", "tests/fonts", "-f", "standard,greek", "ab"]),
+        render(&["ab"])
+    );
+}
+
+#[test]
+fn aligns_fonts_on_their_baselines() {
+    assert_eq!(
+        render(&[
+            "-W",
+            "-d",
+            "tests/fonts",
+            "-f",
+            "standard,greek",
+            "--",
+            "-Ω"
+        ]),
+        concat!(
+            "           \n",
+            "           \n",
+            "  _____    \n",
+            " |_____|/~\\\n",
+            "        | |\n",
+            "        | |\n",
+            "        ~ ~\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn falls_back_in_order() {
+    assert_eq!(
+        render(&["-d", "tests/fonts", "-f", "blocks,greek,standard", "aΩ"]),
+        render(&["-d", "tests/fonts", "-f", "blocks,greek", "aΩ"])
+    );
+    let output = figctl(&["-d", "tests/fonts", "-f", "standard,greek", "Ω€"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "figctl: font standard has no character '€' (U+20AC)\n"
+    );
+}
+
+#[test]
+fn reports_every_font_of_the_chain() {
+    let output = figctl(&["-v", "-d", "tests/fonts", "-f", "standard,greek", "a"]);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "figctl: using font built-in standard\n\
+         figctl: using fallback font tests/fonts/greek.flf\n"
+    );
+    let output = figctl(&["-d", "tests/fonts", "-f", "standard,nonexistent", "a"]);
+    assert_eq!(output.status.code(), Some(3));
+}
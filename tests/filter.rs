@@ -0,0 +1,74 @@
+//! Tests for output filters given with `--filter`.
+
+mod common;
+
+use std::process::Output;
+
+use common::{command, stdout};
+
+fn figctl(args: &[&str]) -> Output {
+    command(args)
+        .env("COLORTERM", "truecolor")
+        .output()
+        .expect("failed to run figctl")
+}
+
+fn render(args: &[&str]) -> String {
+    String::from_utf8(stdout(args, figctl(args))).unwrap()
+}
+
+#[test]
+fn crops_and_borders_in_order() {
+    assert_eq!(
+        render(&["-F", "crop", "-F", "border", "-W", "/"]),
+        concat!(
+            "┌──────┐\n",
+            "│    __│\n",
+            "│   / /│\n",
+            "│  / / │\n",
+            "│ / /  │\n",
+            "│/_/   │\n",
+            "└──────┘\n",
+            "\n",
+        )
+    );
+    let bordered_first = render(&["-F", "border:crop", "-W", "/"]);
+    // The border leaves nothing to crop.
+    assert!(bordered_first.starts_with("┌───────┐\n│     __│\n"));
+}
+
+#[test]
+fn mirrors_and_turns() {
+    assert_eq!(
+        render(&["-F", "crop:flip", "-W", "/"]),
+        "__    \n\\ \\   \n \\ \\  \n  \\ \\ \n   \\_\\\n\n"
+    );
+    assert_eq!(
+        render(&["-F", "crop:flop", "-W", "/"]),
+        "\\‾\\   \n \\ \\  \n  \\ \\ \n   \\ \\\n    ‾‾\n\n"
+    );
+    assert_eq!(
+        render(&["-F", "flip:flop", "Hi"]),
+        render(&["-F", "rotate", "Hi"])
+    );
+    assert_eq!(render(&["-F", "left:right", "Hi"]), render(&["Hi"]));
+}
+
+#[test]
+fn colours_with_metal_and_gay() {
+    let plain = render(&["-F", "metal", "Hi"]);
+    assert_eq!(plain, render(&["Hi"]));
+    let metal = render(&["--color", "always", "-F", "metal", "Hi"]);
+    assert!(metal.contains("\x1b[38;2;92;92;255m"), "{:?}", metal);
+    let gay = render(&["--color", "always", "-F", "crop:gay", "Hi"]);
+    assert!(gay.starts_with(" \x1b[38;2;255;0;0m_"), "{:?}", gay);
+}
+
+#[test]
+fn rejects_unknown_filters() {
+    let output = figctl(&["-F", "crop:blur", "x"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("unknown filter 'blur'"));
+}
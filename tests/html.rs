@@ -0,0 +1,66 @@
+//! Tests for `--format html`.
+
+mod common;
+
+use common::render;
+
+/// Text of an HTML fragment without its tags, with entities decoded.
+fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start + rest[start..].find('>').unwrap() + 1..];
+    }
+    text.push_str(rest);
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn writes_an_escaped_pre_block() {
+    let html = render(&["--format", "html", "<a href='&'>"]);
+    assert!(html.starts_with("<pre class=\"figctl\">"), "{}", html);
+    assert!(html.ends_with("</pre>\n"));
+    assert!(!html.contains("<span"));
+    let plain = render(&["<a href='&'>"]);
+    // The text output ends with an empty line that the block leaves out.
+    assert_eq!(text(&html), plain.strip_suffix('\n').unwrap());
+}
+
+#[test]
+fn colours_with_spans() {
+    let html = render(&["--format", "html", "--color", "palette:red:blue", "ab"]);
+    assert!(html.contains("<span style=\"color: #cd0000\">"), "{}", html);
+    assert!(html.contains("<span style=\"color: #0000ee\">"), "{}", html);
+    assert_eq!(text(&html), render(&["ab"]).strip_suffix('\n').unwrap());
+    let html = render(&["--format", "html", "-F", "metal", "ab"]);
+    assert!(html.contains("<span style=\"color: #5c5cff\">"), "{}", html);
+    let html = render(&["--format", "html", "--color", "never", "-F", "metal", "ab"]);
+    assert!(!html.contains("<span"), "{}", html);
+}
+
+#[test]
+fn writes_standalone_documents() {
+    let html = render(&[
+        "--format",
+        "html",
+        "--standalone",
+        "--font-family",
+        "'Fira Code', monospace",
+        "--background",
+        "black",
+        "x",
+    ]);
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains("<meta charset=\"utf-8\">"));
+    assert!(html.contains("<body style=\"background: #000000\">"));
+    assert!(html.contains(
+        "<pre class=\"figctl\" style=\"font-family: &#39;Fira Code&#39;, monospace; \
+         background: #000000\">"
+    ));
+    assert!(html.ends_with("</pre>\n</body>\n</html>\n"));
+}
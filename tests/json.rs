@@ -0,0 +1,75 @@
+//! Tests for `--format json`.
+
+mod common;
+
+use common::{figctl, stdout};
+use serde_json::{json, Value};
+
+fn render(args: &[&str]) -> Value {
+    serde_json::from_slice(&stdout(args, figctl(args))).expect("invalid JSON")
+}
+
+#[test]
+fn describes_the_font_and_layout() {
+    let document = render(&["--format", "json", "Hi"]);
+    assert_eq!(document["text"], "Hi");
+    assert_eq!(
+        document["font"],
+        json!({"name": "standard", "path": null, "height": 6})
+    );
+    assert_eq!(
+        document["layout"]["horizontal"],
+        json!({"mode": "smushing", "rules": ["equal", "underscore", "hierarchy", "pair"]})
+    );
+    let document = render(&["--format", "json", "-k", "-M", "-1", "Hi"]);
+    assert_eq!(
+        document["layout"],
+        json!({
+            "horizontal": {"mode": "fitting", "rules": []},
+            "vertical": {"mode": "full height", "rules": []},
+        })
+    );
+    let document = render(&["--format", "json", "-f", "tests/fonts/greek.flf", "Ω"]);
+    assert_eq!(document["font"]["name"], "greek");
+    assert_eq!(document["font"]["path"], "tests/fonts/greek.flf");
+}
+
+#[test]
+fn lists_lines_and_character_spans() {
+    let document = render(&["--format", "json", "Hi"]);
+    assert_eq!(document["width"], 9);
+    assert_eq!(document["height"], 6);
+    assert_eq!(document["lines"][1], "| | | (_)");
+    assert_eq!(
+        document["characters"],
+        json!([
+            {"index": 0, "character": "H", "code": 72, "rows": [0, 6], "columns": [0, 7]},
+            {"index": 1, "character": "i", "code": 105, "rows": [0, 6], "columns": [5, 9]},
+        ])
+    );
+}
+
+#[test]
+fn skips_characters_that_were_not_drawn() {
+    let document = render(&["--format", "json", "--missing", "skip", "a\u{E000}b"]);
+    let indices: Vec<&Value> = document["characters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|character| &character["index"])
+        .collect();
+    assert_eq!(indices, [&json!(0), &json!(2)]);
+    assert_eq!(document["text"], "a\u{E000}b");
+}
+
+#[test]
+fn quotes_the_message_the_indices_point_into() {
+    let document = render(&["--format", "json", "A\u{308}b"]);
+    assert_eq!(document["text"], "Äb");
+    assert_eq!(document["characters"][0]["index"], 0);
+    assert_eq!(document["characters"][0]["character"], "Ä");
+    assert_eq!(document["characters"][1]["index"], 1);
+    assert_eq!(document["characters"][1]["character"], "b");
+    let document = render(&["--format", "json", "--strip-accents", "Ä"]);
+    assert_eq!(document["text"], "A");
+}
@@ -0,0 +1,201 @@
+//! Golden tests for the layout modes and the smushing rules.
+//!
+//! `fonts/rules.flf` draws every character as itself twice, with `#` as hardblank, so
+//! the one column where two characters meet shows exactly what a rule did. The expected
+//! horizontal output in `golden/` follows figlet 2.2's `smushem` and `smushamt`, as do
+//! the `standard_*` files, which show the same layouts with the standard font. figlet
+//! does not implement vertical smushing, so the `vertical_*` files follow the FIGfont 2.2
+//! spec instead.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::render;
+
+const RULES: &str = "tests/fonts/rules.flf";
+
+fn golden(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.txt", name));
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+#[test]
+fn full_width() {
+    assert_eq!(render(&["-f", RULES, "-W", "aa\nLR"]), golden("full_width"));
+}
+
+#[test]
+fn fitting() {
+    assert_eq!(render(&["-f", RULES, "-k", "LR\naa"]), golden("fitting"));
+}
+
+#[test]
+fn equal_character_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "1", "aa\nab\n##"]),
+        golden("equal")
+    );
+}
+
+#[test]
+fn underscore_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "2", "_|\n|_\n_/\n__"]),
+        golden("underscore")
+    );
+}
+
+#[test]
+fn hierarchy_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "4", "|/\n(/\n[}\n<(\n//"]),
+        golden("hierarchy")
+    );
+}
+
+#[test]
+fn opposite_pair_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "8", "[]\n()\n}{\n[)"]),
+        golden("pair")
+    );
+}
+
+#[test]
+fn big_x_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "16", "/\\\n\\/\n><\n<>"]),
+        golden("big_x")
+    );
+}
+
+#[test]
+fn hardblank_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-m", "32", "a##a\naa"]),
+        golden("hardblank")
+    );
+}
+
+#[test]
+fn universal_smushing() {
+    // The test font asks for full width and has no rules, so forcing smushing falls
+    // back to universal smushing.
+    assert_eq!(
+        render(&["-f", RULES, "-S", "ab\na##a"]),
+        golden("universal")
+    );
+}
+
+#[test]
+fn font_default_layout() {
+    assert_eq!(render(&["-f", RULES, "-s", "aa\nLR"]), golden("full_width"));
+    assert_eq!(
+        render(&["-f", RULES, "-m", "-2", "aa\nLR"]),
+        golden("full_width")
+    );
+}
+
+#[test]
+fn standard_font() {
+    assert_eq!(render(&["Hello World"]), golden("standard"));
+    assert_eq!(render(&["-k", "Hello"]), golden("standard_fitting"));
+    assert_eq!(render(&["-W", "Hello"]), golden("standard_full_width"));
+    assert_eq!(
+        render(&["-m", "-1", "Hello"]),
+        golden("standard_full_width")
+    );
+    assert_eq!(render(&["-m", "0", "Hello"]), golden("standard_fitting"));
+}
+
+#[test]
+fn vertical_full_height() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "-1", "aa\naa"]),
+        golden("vertical_full_height")
+    );
+}
+
+#[test]
+fn vertical_fitting() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "0", "L \n R\naa\naa"]),
+        golden("vertical_fitting")
+    );
+}
+
+#[test]
+fn vertical_equal_character_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "1", "aa\naa\nbb"]),
+        golden("vertical_equal")
+    );
+}
+
+#[test]
+fn vertical_underscore_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "2", "__\n||\n__\n()"]),
+        golden("vertical_underscore")
+    );
+}
+
+#[test]
+fn vertical_hierarchy_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "4", "||\n//\n{}\n[]"]),
+        golden("vertical_hierarchy")
+    );
+}
+
+#[test]
+fn horizontal_line_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "8", "--", "--\n__\n__\n--"]),
+        golden("vertical_horizontal_line")
+    );
+}
+
+#[test]
+fn vertical_line_rule() {
+    assert_eq!(
+        render(&["-f", RULES, "-M", "16", "||\n||\naa\naa"]),
+        golden("vertical_line")
+    );
+}
+
+#[test]
+fn standard_font_vertical_smushing() {
+    assert_eq!(render(&["Hello\nWorld"]), golden("standard_vertical"));
+    assert_eq!(
+        render(&["-M", "-1", "Hello\nWorld"]),
+        render(&["Hello"]).trim_end_matches('\n').to_string() + "\n" + &render(&["World"])
+    );
+}
+
+#[test]
+fn right_to_left() {
+    assert_eq!(
+        render(&["-f", RULES, "-R", "-l", "-W", "ab\nLR"]),
+        golden("rtl_full_width")
+    );
+    assert_eq!(
+        render(&["-f", RULES, "--direction", "rtl", "-l", "-W", "ab\nLR"]),
+        golden("rtl_full_width")
+    );
+    // Universal smushing lets the character added last win, which is the left one.
+    assert_eq!(
+        render(&["-f", RULES, "-R", "-l", "-S", "ab\nLR"]),
+        golden("rtl_universal")
+    );
+}
+
+#[test]
+fn standard_font_right_to_left() {
+    assert_eq!(render(&["-R", "-w", "60", "Hello"]), golden("standard_rtl"));
+    assert_eq!(render(&["-X", "Hello World"]), golden("standard"));
+}
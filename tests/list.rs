@@ -0,0 +1,37 @@
+//! Tests for the `list-fonts` subcommand.
+
+mod common;
+
+use std::fs;
+
+use common::command;
+
+/// Font directories whose path is not valid UTF-8 still list their own fonts.
+#[cfg(unix)]
+#[test]
+fn lists_fonts_in_directories_with_non_utf8_names() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut name = b"figctl-\xff-".to_vec();
+    name.extend(std::process::id().to_string().bytes());
+    let dir = std::env::temp_dir().join(OsStr::from_bytes(&name));
+    fs::create_dir_all(&dir).unwrap();
+    let manifest = env!("CARGO_MANIFEST_DIR");
+    fs::copy(
+        format!("{}/tests/fonts/greek.flf", manifest),
+        dir.join("greek.flf"),
+    )
+    .unwrap();
+    let output = command(&["list-fonts", "-d"])
+        .arg(&dir)
+        .output()
+        .expect("failed to run figctl");
+    fs::remove_dir_all(&dir).unwrap();
+    let listing = String::from_utf8_lossy(&output.stdout);
+    let greek = listing
+        .split("\n\n")
+        .find(|entry| entry.starts_with("greek ("))
+        .expect("greek is not listed");
+    assert!(greek.contains("height 4,"), "{}", greek);
+}
@@ -0,0 +1,66 @@
+//! Tests for characters the font lacks, `--missing` and `--strip-accents`.
+
+mod common;
+
+use common::{figctl, render};
+
+#[test]
+fn fails_on_missing_characters_by_default() {
+    let output = figctl(&["ok 🙂"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "figctl: font standard has no character '🙂' (U+1F642)\n"
+    );
+    assert_eq!(
+        figctl(&["--missing", "error", "ok 🙂"]).status.code(),
+        Some(5)
+    );
+}
+
+#[test]
+fn skips_or_replaces_missing_characters() {
+    assert_eq!(render(&["--missing", "skip", "ok 🙂!"]), render(&["ok !"]));
+    assert_eq!(
+        render(&["--missing", "replace:?", "ok 🙂"]),
+        render(&["ok ?"])
+    );
+}
+
+#[test]
+fn draws_missing_characters_from_a_fallback_font() {
+    let output = render(&[
+        "-d",
+        "tests/fonts",
+        "--missing",
+        "fallback-font:blocks",
+        "全",
+    ]);
+    assert!(output.contains("\n全\n▀▀\n"), "{}", output);
+    let output = figctl(&["--missing", "fallback-font:no-such-font", "全"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn renders_decomposed_accents_with_precomposed_characters() {
+    assert_eq!(render(&["U\u{308}ber"]), render(&["Über"]));
+}
+
+#[test]
+fn strips_accents_when_asked_to() {
+    assert_eq!(
+        render(&["--strip-accents", "Crème brûlée"]),
+        render(&["Creme brulee"])
+    );
+    // Letters the font has are stripped as well.
+    assert_eq!(render(&["--strip-accents", "Ö"]), render(&["O"]));
+}
+
+#[test]
+fn rejects_unknown_policies() {
+    let output = figctl(&["--missing", "ignore", "x"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("expected error, skip, replace:CHAR or fallback-font:NAME"));
+}
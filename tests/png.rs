@@ -0,0 +1,92 @@
+//! Tests for `--format png`.
+
+mod common;
+
+use std::fs;
+
+use common::{figctl, stdout};
+
+/// Decodes a PNG image into its size and RGBA pixels.
+fn decode(data: &[u8]) -> (u32, u32, Vec<[u8; 4]>) {
+    let mut reader = png::Decoder::new(data).read_info().unwrap();
+    let mut image = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut image).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    let pixels = image
+        .chunks(4)
+        .map(|pixel| pixel.try_into().unwrap())
+        .collect();
+    (info.width, info.height, pixels)
+}
+
+fn png(args: &[&str]) -> (u32, u32, Vec<[u8; 4]>) {
+    decode(&stdout(args, figctl(args)))
+}
+
+#[test]
+fn sizes_the_image_by_cells() {
+    // The standard font draws "Hi" nine columns wide and six rows tall, in cells of 6x8
+    // pixels.
+    let (width, height, _) = png(&["--format", "png", "Hi"]);
+    assert_eq!((width, height), (108, 96));
+    let (width, height, _) = png(&["--format", "png", "--scale", "1", "--padding", "3", "Hi"]);
+    assert_eq!((width, height), (60, 54));
+}
+
+#[test]
+fn draws_in_the_given_colours() {
+    let (_, _, pixels) = png(&["--format", "png", "Hi"]);
+    assert_eq!(pixels[0], [255, 255, 255, 255]);
+    assert!(pixels.contains(&[0, 0, 0, 255]));
+    let (_, _, pixels) = png(&[
+        "--format",
+        "png",
+        "--foreground",
+        "#ffffff",
+        "--background",
+        "#000080",
+        "Hi",
+    ]);
+    assert!(pixels
+        .iter()
+        .all(|&pixel| pixel == [255, 255, 255, 255] || pixel == [0, 0, 128, 255]));
+    let (_, _, pixels) = png(&[
+        "--format",
+        "png",
+        "--transparent",
+        "--color",
+        "#ff0000",
+        "Hi",
+    ]);
+    assert_eq!(pixels[0][3], 0);
+    assert!(pixels.contains(&[255, 0, 0, 255]));
+    assert!(!pixels.contains(&[0, 0, 0, 255]));
+}
+
+#[test]
+fn writes_the_image_to_a_file() {
+    let path = std::env::temp_dir().join(format!("figctl-test-{}.png", std::process::id()));
+    let output = figctl(&["--format", "png", "--out", path.to_str().unwrap(), "Hi"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let data = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(decode(&data).0, 108);
+}
+
+#[test]
+fn rejects_conflicting_options() {
+    let output = figctl(&[
+        "--format",
+        "png",
+        "--transparent",
+        "--background",
+        "red",
+        "Hi",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = figctl(&["--format", "png", "--scale", "0", "Hi"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = figctl(&["--format", "png", "--out", "/nonexistent/banner.png", "Hi"]);
+    assert_eq!(output.status.code(), Some(7));
+}
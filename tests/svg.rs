@@ -0,0 +1,84 @@
+//! Tests for `--format svg`.
+
+mod common;
+
+use common::{figctl, render};
+
+#[test]
+fn sizes_the_image_by_cells() {
+    // The standard font draws "Hi" nine columns wide and six rows tall.
+    let image = render(&["--format", "svg", "Hi"]);
+    assert!(image.starts_with(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"90\" height=\"120\" \
+         viewBox=\"0 0 90 120\">\n"
+    ));
+    let image = render(&[
+        "--format",
+        "svg",
+        "--cell-size",
+        "6x12",
+        "--padding",
+        "4",
+        "Hi",
+    ]);
+    assert!(image.contains("width=\"62\" height=\"80\""), "{}", image);
+    assert!(image.ends_with("</g>\n</svg>\n"));
+}
+
+#[test]
+fn writes_text_rows() {
+    let image = render(&[
+        "--format",
+        "svg",
+        "--font-family",
+        "Iosevka",
+        "--foreground",
+        "white",
+        "--background",
+        "#123456",
+        "--color",
+        "red",
+        "Hi",
+    ]);
+    assert!(image.contains("<rect width=\"90\" height=\"120\" fill=\"#123456\"/>"));
+    assert!(image.contains("<g font-family=\"Iosevka\" font-size=\"20\" fill=\"#e5e5e5\""));
+    assert!(image.contains(
+        "<text y=\"36\"><tspan x=\"0\" textLength=\"10\" lengthAdjust=\"spacingAndGlyphs\" \
+         fill=\"#cd0000\">|</tspan>"
+    ));
+    assert_eq!(image.matches("<text ").count(), 5);
+}
+
+#[test]
+fn draws_cells_as_rectangles() {
+    let image = render(&[
+        "-d",
+        "tests/fonts",
+        "-f",
+        "blocks",
+        "--format",
+        "svg",
+        "--cells",
+        "a",
+    ]);
+    assert_eq!(
+        image,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"40\" \
+         viewBox=\"0 0 20 40\">\n\
+         <g fill=\"#000000\">\n\
+         <rect x=\"0\" y=\"0\" width=\"10\" height=\"20\"/>\n\
+         <rect x=\"10\" y=\"0\" width=\"10\" height=\"20\"/>\n\
+         <rect x=\"0\" y=\"20\" width=\"10\" height=\"10\"/>\n\
+         <rect x=\"10\" y=\"20\" width=\"10\" height=\"10\"/>\n\
+         </g>\n\
+         </svg>\n"
+    );
+}
+
+#[test]
+fn rejects_invalid_cell_sizes() {
+    for size in ["10", "0x20", "ax20"] {
+        let output = figctl(&["--format", "svg", "--cell-size", size, "x"]);
+        assert_eq!(output.status.code(), Some(2), "{}", size);
+    }
+}
@@ -0,0 +1,99 @@
+//! Tests for TOIlet fonts and sub-characters wider than one column.
+//!
+//! `fonts/blocks.tlf` draws every character as a full block next to the character, on top
+//! of two upper half blocks, and defines U+5168 as a double-width character and U+FEFB as
+//! lam and alef, which unicode-width measures as one column together but two apart.
+
+mod common;
+
+use common::render;
+use figctl::{font, Justification, Renderer, SearchPath};
+
+const FONT_DIR: &str = "tests/fonts";
+
+fn blocks() -> Renderer {
+    let dir = [std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(FONT_DIR)];
+    Renderer::new(font::load(Some("blocks"), &SearchPath::new(&dir)).unwrap())
+}
+
+#[test]
+fn finds_toilet_fonts_by_name() {
+    assert_eq!(
+        render(&["-d", FONT_DIR, "-f", "blocks", "ab"]),
+        "█a█b\n▀▀▀▀\n\n"
+    );
+    assert_eq!(
+        render(&["-f", "tests/fonts/blocks.tlf", "ab"]),
+        "█a█b\n▀▀▀▀\n\n"
+    );
+}
+
+#[test]
+fn parses_utf8_sub_characters() {
+    let font = blocks().font().figfont.clone();
+    assert_eq!(font.header.hardblank, '$');
+    assert_eq!(font.character('ß').unwrap(), ["█ß", "▀▀"]);
+    assert_eq!(font.character('全').unwrap(), ["全", "▀▀"]);
+}
+
+#[test]
+fn measures_double_width_characters_in_columns() {
+    let banner = blocks().render("a全b").unwrap();
+    assert_eq!(banner.rows, ["█a全█b", "▀▀▀▀▀▀"]);
+    assert_eq!(banner.width(), 6);
+    let banner = blocks()
+        .justification(Justification::Right)
+        .width(Some(13))
+        .render("a全b")
+        .unwrap();
+    assert_eq!(banner.rows, ["      █a全█b", "      ▀▀▀▀▀▀"]);
+}
+
+#[test]
+fn measures_rows_one_sub_character_at_a_time() {
+    let banner = blocks().render("\u{FEFB}").unwrap();
+    assert_eq!(banner.width(), 2);
+    assert_eq!(
+        render(&["-d", FONT_DIR, "-f", "blocks", "-F", "flip", "\u{FEFB}"]),
+        "\u{627}\u{644}\n\u{627}\u{644}\n\n"
+    );
+    let html = render(&[
+        "-d",
+        FONT_DIR,
+        "-f",
+        "blocks",
+        "--color",
+        "palette:red",
+        "--format",
+        "html",
+        "\u{FEFB}",
+    ]);
+    assert!(html.contains("\u{644}\u{627}</span>"), "{}", html);
+}
+
+#[test]
+fn wraps_by_columns() {
+    // Both words are six columns wide, but only five characters long.
+    assert_eq!(
+        render(&["-d", FONT_DIR, "-f", "blocks", "-w", "11", "全ab 全ab"]),
+        "全█a█b\n▀▀▀▀▀▀\n全█a█b\n▀▀▀▀▀▀\n\n"
+    );
+}
+
+#[test]
+fn never_smushes_double_width_characters() {
+    assert_eq!(
+        render(&["-d", FONT_DIR, "-f", "blocks", "-S", "a全b"]),
+        "█a全█b\n▀▀▀▀▀▀\n\n"
+    );
+}
+
+#[test]
+fn lists_toilet_fonts() {
+    let output = render(&["list-fonts", "-d", FONT_DIR]);
+    assert!(
+        output.contains("blocks (tests/fonts/blocks.tlf)"),
+        "{}",
+        output
+    );
+}